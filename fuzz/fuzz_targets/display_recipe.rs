@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pz_data::Recipe;
+
+fuzz_target!(|recipe: Recipe| {
+    let text = recipe.to_string();
+    let _ = pz_data::recipe::<nom::error::Error<&str>>(&text);
+});
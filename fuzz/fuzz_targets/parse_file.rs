@@ -0,0 +1,19 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pz_data::ParseOptions;
+
+fuzz_target!(|data: &str| {
+    let _ = pz_data::parse_file::<nom::error::Error<&str>>(data);
+
+    let mut lenient = ParseOptions::default();
+    lenient.allow_trailing_comma = true;
+    lenient.collect_unknown_fields = true;
+    lenient.lenient_bools = true;
+    lenient.allow_comments = true;
+    lenient.attach_comments = true;
+    lenient.multiline_strings = true;
+    lenient.normalize_line_endings = true;
+    lenient.semicolon_terminators = true;
+    let _ = pz_data::parse_file_with_options::<nom::error::Error<&str>>(data, lenient);
+});
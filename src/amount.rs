@@ -0,0 +1,121 @@
+use std::{fmt, ops::Add};
+
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{digit0, digit1, u32},
+    combinator::{map, map_parser, recognize},
+    error::ParseError,
+    number::complete::float,
+    sequence::tuple,
+    IResult,
+};
+
+/// How much of an ingredient is used. Whole items are counted; fluids are
+/// measured in fractions, written with a decimal point as in `Water=0.5`.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Amount {
+    Int(u32),
+    Float(f32),
+}
+
+impl Amount {
+    pub fn as_f32(&self) -> f32 {
+        match *self {
+            Amount::Int(count) => count as f32,
+            Amount::Float(amount) => amount,
+        }
+    }
+}
+
+impl Default for Amount {
+    fn default() -> Self {
+        Amount::Int(1)
+    }
+}
+
+impl From<u32> for Amount {
+    fn from(count: u32) -> Self {
+        Amount::Int(count)
+    }
+}
+
+impl From<f32> for Amount {
+    fn from(amount: f32) -> Self {
+        Amount::Float(amount)
+    }
+}
+
+/// Counts stay whole when both sides are and the sum fits in a `u32`;
+/// otherwise the sum is fractional.
+impl Add for Amount {
+    type Output = Amount;
+
+    fn add(self, other: Amount) -> Amount {
+        match (self, other) {
+            (Amount::Int(a), Amount::Int(b)) => match a.checked_add(b) {
+                Some(sum) => Amount::Int(sum),
+                None => Amount::Float(a as f32 + b as f32),
+            },
+            (a, b) => Amount::Float(a.as_f32() + b.as_f32()),
+        }
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Amount::Int(count) => write!(f, "{}", count),
+            // Keep the decimal point so the amount parses back as a float.
+            Amount::Float(amount) if amount.fract() == 0.0 => write!(f, "{}.0", amount),
+            Amount::Float(amount) => write!(f, "{}", amount),
+        }
+    }
+}
+
+pub fn amount<'a, E>(input: &'a str) -> IResult<&'a str, Amount, E>
+where
+    E: ParseError<&'a str>,
+{
+    alt((
+        map(
+            map_parser(recognize(tuple((digit1, tag("."), digit0))), float),
+            Amount::Float,
+        ),
+        map(u32, Amount::Int),
+    ))(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Result<T> = IResult<&'static str, T, nom::error::Error<&'static str>>;
+
+    #[test]
+    fn parse_fractional_amount() {
+        let amount_res: Result<Amount> = amount("0.5,");
+
+        assert_eq!(Ok((",", Amount::Float(0.5))), amount_res);
+    }
+
+    #[test]
+    fn parse_whole_amount() {
+        let amount_res: Result<Amount> = amount("3,");
+
+        assert_eq!(Ok((",", Amount::Int(3))), amount_res);
+    }
+
+    #[test]
+    fn whole_float_keeps_its_decimal_point() {
+        assert_eq!("2.0", Amount::Float(2.0).to_string());
+    }
+
+    #[test]
+    fn overflowing_counts_sum_as_float() {
+        let sum = Amount::Int(u32::MAX) + Amount::Int(1);
+
+        assert_eq!(Amount::Float(u32::MAX as f32 + 1.0), sum);
+    }
+}
@@ -0,0 +1,94 @@
+use std::{convert::Infallible, fmt, str::FromStr};
+
+/// The animation a character plays while crafting a recipe. Animations
+/// outside the vanilla set are kept verbatim in `Other`.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AnimNode {
+    BuildLow,
+    Chopping,
+    Craft,
+    Digging,
+    Disassemble,
+    RemoveBullet,
+    RipSheets,
+    SawLog,
+    Other(String),
+}
+
+impl AnimNode {
+    /// The animation as it is written in script files.
+    pub fn as_str(&self) -> &str {
+        match self {
+            AnimNode::BuildLow => "BuildLow",
+            AnimNode::Chopping => "Chopping",
+            AnimNode::Craft => "Craft",
+            AnimNode::Digging => "Digging",
+            AnimNode::Disassemble => "Disassemble",
+            AnimNode::RemoveBullet => "RemoveBullet",
+            AnimNode::RipSheets => "RipSheets",
+            AnimNode::SawLog => "SawLog",
+            AnimNode::Other(anim_node) => anim_node,
+        }
+    }
+}
+
+impl From<&str> for AnimNode {
+    fn from(anim_node: &str) -> Self {
+        match anim_node {
+            "BuildLow" => AnimNode::BuildLow,
+            "Chopping" => AnimNode::Chopping,
+            "Craft" => AnimNode::Craft,
+            "Digging" => AnimNode::Digging,
+            "Disassemble" => AnimNode::Disassemble,
+            "RemoveBullet" => AnimNode::RemoveBullet,
+            "RipSheets" => AnimNode::RipSheets,
+            "SawLog" => AnimNode::SawLog,
+            other => AnimNode::Other(other.to_string()),
+        }
+    }
+}
+
+impl From<String> for AnimNode {
+    fn from(anim_node: String) -> Self {
+        match AnimNode::from(anim_node.as_str()) {
+            AnimNode::Other(_) => AnimNode::Other(anim_node),
+            known => known,
+        }
+    }
+}
+
+impl FromStr for AnimNode {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(AnimNode::from(s))
+    }
+}
+
+impl fmt::Display for AnimNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_anim_node() {
+        let anim_node = AnimNode::from("Chopping");
+
+        assert_eq!(AnimNode::Chopping, anim_node);
+        assert_eq!("Chopping", anim_node.as_str());
+    }
+
+    #[test]
+    fn unknown_anim_node() {
+        let anim_node = AnimNode::from("FixGenerator");
+
+        assert_eq!(AnimNode::Other(String::from("FixGenerator")), anim_node);
+        assert_eq!("FixGenerator", anim_node.to_string());
+    }
+}
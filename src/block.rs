@@ -1,35 +1,57 @@
 use nom::{
     bytes::complete::tag,
-    character::complete::{multispace1, space1},
-    error::ParseError,
+    character::complete::{multispace0, multispace1, space1},
+    error::{ErrorKind, ParseError},
     multi::separated_list1,
     sequence::{delimited, pair},
-    AsChar, IResult, InputLength, InputTake, InputTakeAtPosition, Parser, Slice,
+    AsChar, IResult, InputLength, InputTake, InputTakeAtPosition, Parser,
 };
 
+use crate::{comment::inline_separator, ParseOptions};
+
 fn non_curly_brace<T, E: ParseError<T>>(input: T) -> IResult<T, T, E>
 where
     T: InputTakeAtPosition,
     <T as InputTakeAtPosition>::Item: AsChar,
 {
-    input.split_at_position1_complete(|item| item.as_char() == '{', nom::error::ErrorKind::Char)
+    input.split_at_position1_complete(|item| item.as_char() == '{', ErrorKind::Char)
 }
 
-fn string_with_spaces_delimited_by_open_brace<'a, E: ParseError<&'a str>>(
+pub(crate) fn string_with_spaces_delimited_by_open_brace<'a, E: ParseError<&'a str>>(
     input: &'a str,
 ) -> IResult<&'a str, &'a str, E> {
-    let (_tail, name_trailing_space_and_brace) = non_curly_brace(<&str>::clone(&input))?;
-    let len = name_trailing_space_and_brace.input_len();
-    let name_and_trailing_space = name_trailing_space_and_brace.slice(..len - 1);
+    let (tail, name_and_trailing_space) = non_curly_brace(<&str>::clone(&input))?;
+    if tail.is_empty() {
+        return Err(nom::Err::Error(E::from_error_kind(tail, ErrorKind::Char)));
+    }
     let trimmed_name = name_and_trailing_space.trim_end();
     let name_len = trimmed_name.input_len();
+    if name_len == 0 {
+        return Err(nom::Err::Error(E::from_error_kind(input, ErrorKind::Alpha)));
+    }
 
     Ok(input.take_split(name_len))
 }
 
-pub fn block<'a, 'b, F, O, E>(mut item: F) -> impl FnMut(&'a str) -> IResult<&'a str, O, E>
+/// The name of a block: everything up to the opening brace, without the
+/// whitespace before the brace. The brace itself is left unparsed.
+///
+/// ```
+/// use pz_data::block_name;
+///
+/// let (rest, name) = block_name::<nom::error::Error<&str>>("Foo Bar {").unwrap();
+/// assert_eq!("Foo Bar", name);
+/// assert_eq!(" {", rest);
+/// ```
+pub fn block_name<'a, E>(input: &'a str) -> IResult<&'a str, &'a str, E>
+where
+    E: ParseError<&'a str>,
+{
+    string_with_spaces_delimited_by_open_brace(input)
+}
+
+pub fn block<'a, F, O, E>(mut item: F) -> impl FnMut(&'a str) -> IResult<&'a str, O, E>
 where
-    'b: 'a,
     F: Parser<&'a str, O, E>,
     E: ParseError<&'a str>,
 {
@@ -37,7 +59,7 @@ where
         delimited(
             pair(tag("{"), multispace1),
             |input| item.parse(input),
-            pair(multispace1, tag("}")),
+            pair(multispace0, tag("}")),
         )(input)
     }
 }
@@ -45,9 +67,8 @@ where
 pub fn unnamed_block<'a, 'b, F, O, E>(
     block_tag: &'b str,
     mut item: F,
-) -> impl FnMut(&'a str) -> IResult<&'a str, O, E>
+) -> impl FnMut(&'a str) -> IResult<&'a str, O, E> + use<'a, 'b, F, O, E>
 where
-    'b: 'a,
     F: Parser<&'a str, O, E>,
     E: ParseError<&'a str>,
 {
@@ -59,20 +80,65 @@ where
     }
 }
 
+pub fn unnamed_block_repeated<'a, 'b, F, O, E>(
+    block_tag: &'b str,
+    mut item: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>, E> + use<'a, 'b, F, O, E>
+where
+    F: Parser<&'a str, O, E>,
+    E: ParseError<&'a str>,
+{
+    move |input: &'a str| {
+        unnamed_block(
+            block_tag,
+            separated_list1(multispace1, |input| item.parse(input)),
+        )(input)
+    }
+}
+
+/// A `tag name { ... }` block. The tag only has to live as long as the
+/// returned parser; the name and item it parses borrow from the input.
 pub fn named_block<'a, 'b, F, O, E>(
     block_tag: &'b str,
+    item: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (&'a str, O), E> + use<'a, 'b, F, O, E>
+where
+    F: Parser<&'a str, O, E>,
+    E: ParseError<&'a str>,
+{
+    named_block_spaced_by(block_tag, space1, item)
+}
+
+/// Like `named_block`, but comments between the tag and the name are
+/// skipped when the options allow them.
+pub(crate) fn named_block_with_options<'a, 'b, F, O, E>(
+    block_tag: &'b str,
+    options: ParseOptions,
+    item: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (&'a str, O), E> + use<'a, 'b, F, O, E>
+where
+    F: Parser<&'a str, O, E>,
+    E: ParseError<&'a str>,
+{
+    named_block_spaced_by(block_tag, inline_separator(options), item)
+}
+
+fn named_block_spaced_by<'a, 'b, S, P, F, O, E>(
+    block_tag: &'b str,
+    mut spacer: S,
     mut item: F,
-) -> impl FnMut(&'a str) -> IResult<&'a str, (&'a str, O), E>
+) -> impl FnMut(&'a str) -> IResult<&'a str, (&'a str, O), E> + use<'a, 'b, S, P, F, O, E>
 where
-    'b: 'a,
+    S: Parser<&'a str, P, E>,
     F: Parser<&'a str, O, E>,
     E: ParseError<&'a str>,
 {
     move |input: &'a str| {
         let (input, _) = tag(block_tag)(input)?;
-        let (input, _) = space1(input)?;
+        let (input, _) = spacer.parse(input)?;
         let (input, name) = string_with_spaces_delimited_by_open_brace(input)?;
-        let (input, _) = multispace1(input)?;
+        // Minified files put the brace straight after the name.
+        let (input, _) = multispace0(input)?;
         let (input, parsed_item) = block(|input| item.parse(input))(input)?;
 
         Ok((input, (name, parsed_item)))
@@ -82,9 +148,8 @@ where
 pub fn named_block_repeated<'a, 'b, F, O, E>(
     block_tag: &'b str,
     mut item: F,
-) -> impl FnMut(&'a str) -> IResult<&'a str, (&'a str, Vec<O>), E>
+) -> impl FnMut(&'a str) -> IResult<&'a str, (&'a str, Vec<O>), E> + use<'a, 'b, F, O, E>
 where
-    'b: 'a,
     F: Parser<&'a str, O, E>,
     E: ParseError<&'a str>,
 {
@@ -96,18 +161,87 @@ where
     }
 }
 
+/// The whitespace that separated a repeated item from the item before it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Spacing {
+    pub newlines: usize,
+}
+
+impl Spacing {
+    fn of(whitespace: &str) -> Self {
+        Spacing {
+            newlines: whitespace.matches('\n').count(),
+        }
+    }
+
+    pub fn blank_lines(&self) -> usize {
+        self.newlines.saturating_sub(1)
+    }
+}
+
+type SpacedItems<O> = Vec<(O, Spacing)>;
+
+/// Like `named_block_repeated`, but each item is paired with the spacing
+/// that preceded it so that a formatter can reproduce the layout. The first
+/// item always has the default spacing.
+pub fn named_block_repeated_spaced<'a, 'b, F, O, E>(
+    block_tag: &'b str,
+    mut item: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (&'a str, SpacedItems<O>), E> + use<'a, 'b, F, O, E>
+where
+    F: Parser<&'a str, O, E>,
+    E: ParseError<&'a str>,
+{
+    move |input: &'a str| {
+        named_block(block_tag, |input: &'a str| {
+            let (mut input, first) = item.parse(input)?;
+            let mut items = vec![(first, Spacing::default())];
+            while let Ok((after_space, whitespace)) = multispace1::<_, E>(input) {
+                match item.parse(after_space) {
+                    Ok((rest, next)) => {
+                        items.push((next, Spacing::of(whitespace)));
+                        input = rest;
+                    }
+                    Err(nom::Err::Error(_)) => break,
+                    Err(err) => return Err(err),
+                }
+            }
+            Ok((input, items))
+        })(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     use nom::{
-        character::complete::digit1,
+        character::complete::{alphanumeric1, digit1},
         combinator::map_res,
         sequence::{pair, preceded},
     };
 
     type Result<T> = IResult<&'static str, T, nom::error::Error<&'static str>>;
 
+    /// The name borrows from `input` alone, so it may outlive the tag.
+    fn block_name_of<'a>(input: &'a str, block_tag: &str) -> Option<&'a str> {
+        let block_res: IResult<&'a str, (&'a str, &'a str), nom::error::Error<&'a str>> =
+            named_block(block_tag, alphanumeric1)(input);
+        block_res.ok().map(|(_, (name, _))| name)
+    }
+
+    #[test]
+    fn parsed_name_outlives_block_tag() {
+        let input = String::from("recipe Foo { bar }");
+        let name = {
+            let block_tag = String::from("recipe");
+            block_name_of(&input, &block_tag)
+        };
+
+        assert_eq!(Some("Foo"), name);
+    }
+
     #[test]
     fn parse_container_block() {
         let test_text = "container Foo { foo }";
@@ -188,4 +322,85 @@ mod tests {
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn missing_brace_after_multibyte_name_is_an_error() {
+        // Found by fuzzing: this used to slice through the middle of `é`.
+        let block_res: Result<(&str, &str)> = named_block("item", tag("Nil"))("item Café");
+
+        assert!(block_res.is_err());
+    }
+
+    #[test]
+    fn name_without_brace_is_an_error() {
+        let name_res: Result<&str> = string_with_spaces_delimited_by_open_brace("NoBrace");
+        assert!(name_res.is_err());
+
+        let block_res: Result<(&str, &str)> = named_block("item", tag("Nil"))("item NoBrace");
+        assert!(block_res.is_err());
+    }
+
+    #[test]
+    fn empty_name_is_an_error() {
+        let name_res: Result<&str> = string_with_spaces_delimited_by_open_brace(" \n{ Nil }");
+        assert!(name_res.is_err());
+
+        let block_res: Result<(&str, &str)> = named_block("item", tag("Nil"))("item {");
+        assert!(block_res.is_err());
+
+        let block_res: Result<(&str, &str)> = named_block("item", tag("Nil"))("item \n{ Nil }");
+        assert!(block_res.is_err());
+    }
+
+    #[test]
+    fn parse_repeated_block_with_spacing() {
+        let test_text = "module Base
+{
+  foo
+  foo
+
+  foo
+}";
+        let expected = (
+            "Base",
+            vec![
+                ("foo", Spacing { newlines: 0 }),
+                ("foo", Spacing { newlines: 1 }),
+                ("foo", Spacing { newlines: 2 }),
+            ],
+        );
+
+        let block_res: Result<(&str, Vec<(&str, Spacing)>)> =
+            named_block_repeated_spaced("module", tag("foo"))(test_text);
+        let (_, actual) = block_res.expect("failed to parse block");
+
+        assert_eq!(expected, actual);
+        assert_eq!(0, actual.1[1].1.blank_lines());
+        assert_eq!(1, actual.1[2].1.blank_lines());
+    }
+
+    #[test]
+    fn parse_name_with_brace_on_same_line() {
+        let test_text = "item Foo {
+  Nil
+}";
+        let expected = ("Foo", "Nil");
+
+        let block_res: Result<(&str, &str)> = named_block("item", tag("Nil"))(test_text);
+        let (_, actual) = block_res.expect("failed to parse block");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn parse_unnamed_repeated_block() {
+        let test_text = "imports { Base ModA ModB }";
+        let expected = vec!["Base", "ModA", "ModB"];
+
+        let block_res: Result<Vec<&str>> =
+            unnamed_block_repeated("imports", alphanumeric1)(test_text);
+        let (_, actual) = block_res.expect("failed to parse block");
+
+        assert_eq!(expected, actual);
+    }
 }
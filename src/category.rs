@@ -0,0 +1,112 @@
+use std::{convert::Infallible, fmt, str::FromStr};
+
+/// The crafting menu category of a recipe. Categories outside the vanilla
+/// set are kept verbatim in `Other`.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RecipeCategory {
+    Carpentry,
+    Cooking,
+    Electrical,
+    Farming,
+    Fishing,
+    General,
+    Health,
+    Metalwork,
+    Smithing,
+    Survivalist,
+    Tailoring,
+    Trapper,
+    Welding,
+    Other(String),
+}
+
+impl RecipeCategory {
+    /// The category as it is written in script files.
+    pub fn as_str(&self) -> &str {
+        match self {
+            RecipeCategory::Carpentry => "Carpentry",
+            RecipeCategory::Cooking => "Cooking",
+            RecipeCategory::Electrical => "Electrical",
+            RecipeCategory::Farming => "Farming",
+            RecipeCategory::Fishing => "Fishing",
+            RecipeCategory::General => "General",
+            RecipeCategory::Health => "Health",
+            RecipeCategory::Metalwork => "Metalwork",
+            RecipeCategory::Smithing => "Smithing",
+            RecipeCategory::Survivalist => "Survivalist",
+            RecipeCategory::Tailoring => "Tailoring",
+            RecipeCategory::Trapper => "Trapper",
+            RecipeCategory::Welding => "Welding",
+            RecipeCategory::Other(category) => category,
+        }
+    }
+}
+
+impl From<&str> for RecipeCategory {
+    fn from(category: &str) -> Self {
+        match category {
+            "Carpentry" => RecipeCategory::Carpentry,
+            "Cooking" => RecipeCategory::Cooking,
+            "Electrical" => RecipeCategory::Electrical,
+            "Farming" => RecipeCategory::Farming,
+            "Fishing" => RecipeCategory::Fishing,
+            "General" => RecipeCategory::General,
+            "Health" => RecipeCategory::Health,
+            "Metalwork" => RecipeCategory::Metalwork,
+            "Smithing" => RecipeCategory::Smithing,
+            "Survivalist" => RecipeCategory::Survivalist,
+            "Tailoring" => RecipeCategory::Tailoring,
+            "Trapper" => RecipeCategory::Trapper,
+            "Welding" => RecipeCategory::Welding,
+            other => RecipeCategory::Other(other.to_string()),
+        }
+    }
+}
+
+impl From<String> for RecipeCategory {
+    fn from(category: String) -> Self {
+        match RecipeCategory::from(category.as_str()) {
+            RecipeCategory::Other(_) => RecipeCategory::Other(category),
+            known => known,
+        }
+    }
+}
+
+impl FromStr for RecipeCategory {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(RecipeCategory::from(s))
+    }
+}
+
+impl fmt::Display for RecipeCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_category() {
+        let category = RecipeCategory::from("Farming");
+
+        assert_eq!(RecipeCategory::Farming, category);
+        assert_eq!("Farming", category.as_str());
+    }
+
+    #[test]
+    fn unknown_category_is_kept_verbatim() {
+        let category: RecipeCategory = "Cooking Skill".parse().unwrap();
+
+        assert_eq!(
+            RecipeCategory::Other(String::from("Cooking Skill")),
+            category
+        );
+        assert_eq!("Cooking Skill", category.as_str());
+    }
+}
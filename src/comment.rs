@@ -0,0 +1,147 @@
+use nom::{
+    branch::alt,
+    bytes::complete::{is_not, tag, take_until},
+    character::complete::{multispace0, multispace1, space1},
+    combinator::{opt, recognize},
+    error::ParseError,
+    multi::{many0, many1},
+    sequence::{delimited, preceded, terminated},
+    IResult,
+};
+
+use crate::ParseOptions;
+
+pub fn comment<'a, E>(input: &'a str) -> IResult<&'a str, &'a str, E>
+where
+    E: ParseError<&'a str>,
+{
+    alt((
+        delimited(tag("/*"), take_until("*/"), tag("*/")),
+        preceded(tag("//"), recognize(opt(is_not("\r\n")))),
+    ))(input)
+}
+
+/// Whitespace between two items. Comments count as whitespace when the
+/// options allow them.
+pub(crate) fn separator<'a, E>(
+    options: ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str, E>
+where
+    E: ParseError<&'a str>,
+{
+    move |input: &'a str| {
+        if options.comments_allowed() {
+            recognize(many1(alt((multispace1, comment))))(input)
+        } else {
+            multispace1(input)
+        }
+    }
+}
+
+/// Whitespace within a line, such as between a block tag and its name.
+/// Block comments count as whitespace when the options allow them.
+pub(crate) fn inline_separator<'a, E>(
+    options: ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str, E>
+where
+    E: ParseError<&'a str>,
+{
+    move |input: &'a str| {
+        if options.comments_allowed() {
+            recognize(many1(alt((space1, comment))))(input)
+        } else {
+            space1(input)
+        }
+    }
+}
+
+/// Optional whitespace, including comments when the options allow them.
+pub(crate) fn whitespace0<'a, E>(
+    options: ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str, E>
+where
+    E: ParseError<&'a str>,
+{
+    move |input: &'a str| {
+        if options.comments_allowed() {
+            recognize(many0(alt((multispace1, comment))))(input)
+        } else {
+            multispace0(input)
+        }
+    }
+}
+
+/// Comments at the very start of a block body. The whitespace that
+/// precedes them has already been consumed by the block.
+pub(crate) fn leading_comments<'a, E>(
+    options: ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<&'a str>, E>
+where
+    E: ParseError<&'a str>,
+{
+    move |input: &'a str| {
+        if options.comments_allowed() {
+            many0(terminated(comment, multispace0))(input)
+        } else {
+            Ok((input, Vec::new()))
+        }
+    }
+}
+
+/// Comments after the last item of a block body. The whitespace before
+/// the closing brace is left for the block to consume.
+pub(crate) fn trailing_comments<'a, E>(
+    options: ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<&'a str>, E>
+where
+    E: ParseError<&'a str>,
+{
+    move |input: &'a str| {
+        if options.comments_allowed() {
+            many0(preceded(multispace0, comment))(input)
+        } else {
+            Ok((input, Vec::new()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Result<T> = IResult<&'static str, T, nom::error::Error<&'static str>>;
+
+    #[test]
+    fn parse_block_comment() {
+        let comment_res: Result<&str> = comment("/* a\n b */rest");
+        let (rest, actual) = comment_res.expect("failed to parse comment");
+
+        assert_eq!("rest", rest);
+        assert_eq!(" a\n b ", actual);
+    }
+
+    #[test]
+    fn parse_line_comment() {
+        let comment_res: Result<&str> = comment("// note\nrest");
+        let (rest, actual) = comment_res.expect("failed to parse comment");
+
+        assert_eq!("\nrest", rest);
+        assert_eq!(" note", actual);
+    }
+
+    #[test]
+    fn separator_skips_comments_only_when_allowed() {
+        let options = ParseOptions {
+            allow_comments: true,
+            ..ParseOptions::default()
+        };
+
+        let allowed: Result<&str> = separator(options)(" /* c */\n  next");
+        let (rest, _) = allowed.expect("failed to parse separator");
+        assert_eq!("next", rest);
+
+        let strict: Result<&str> = separator(ParseOptions::default())(" /* c */\n  next");
+        let (rest, _) = strict.expect("failed to parse separator");
+        assert_eq!("/* c */\n  next", rest);
+    }
+}
@@ -0,0 +1,167 @@
+use nom::{
+    branch::alt,
+    combinator::{cond, map},
+    error::{ContextError, ParseError},
+    IResult,
+};
+
+use crate::{
+    item_with_options, leading_comments, option_with_options, recipe_with_options, Item, Option_,
+    ParseOptions, Recipe,
+};
+
+/// A definition that can be identified by its block name.
+pub trait Named {
+    fn name(&self) -> &str;
+}
+
+/// Any of the block types that can appear inside a module.
+#[derive(Debug, PartialEq)]
+pub enum Definition {
+    Recipe(Box<Recipe>),
+    Item(Item),
+    Option(Option_),
+}
+
+impl Named for Definition {
+    fn name(&self) -> &str {
+        match self {
+            Definition::Recipe(recipe) => recipe.name(),
+            Definition::Item(item) => item.name(),
+            Definition::Option(option) => option.name(),
+        }
+    }
+}
+
+impl Definition {
+    /// The block name, whatever kind of definition this is. The same as
+    /// `Named::name`, without needing the trait in scope.
+    pub fn name(&self) -> &str {
+        Named::name(self)
+    }
+
+    pub(crate) fn extra_mut(&mut self) -> &mut Vec<(String, String)> {
+        match self {
+            Definition::Recipe(recipe) => recipe.extra_mut(),
+            Definition::Item(item) => item.extra_mut(),
+            Definition::Option(option) => option.extra_mut(),
+        }
+    }
+
+    /// Whether both are the same kind of block, so that one can override
+    /// the other.
+    pub(crate) fn same_kind(&self, other: &Definition) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    fn set_leading_comments(&mut self, comments: Vec<String>) {
+        match self {
+            Definition::Recipe(recipe) => recipe.set_leading_comments(comments),
+            Definition::Item(item) => item.set_leading_comments(comments),
+            Definition::Option(option) => option.set_leading_comments(comments),
+        }
+    }
+}
+
+pub fn definition<'a, E>(
+    options: ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Definition, E>
+where
+    E: ParseError<&'a str> + ContextError<&'a str>,
+{
+    move |input: &'a str| {
+        let (input, comments) = cond(options.attach_comments, leading_comments(options))(input)?;
+        let (input, mut definition) = alt((
+            map(recipe_with_options(options), |recipe| {
+                Definition::Recipe(Box::new(recipe))
+            }),
+            map(item_with_options(options), Definition::Item),
+            map(option_with_options(options), Definition::Option),
+        ))(input)?;
+        trace!(
+            "parsed {} {}",
+            match &definition {
+                Definition::Recipe(_) => "recipe",
+                Definition::Item(_) => "item",
+                Definition::Option(_) => "option",
+            },
+            definition.name()
+        );
+        if let Some(comments) = comments {
+            definition
+                .set_leading_comments(comments.into_iter().map(|c| c.trim().to_string()).collect());
+        }
+        Ok((input, definition))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Result<T> = IResult<&'static str, T, nom::error::Error<&'static str>>;
+
+    #[test]
+    fn list_names_of_mixed_definitions() {
+        let module_text = "module Base
+{
+    item RedRadish
+    {
+        Type = Food,
+    }
+
+    recipe Make Mildew Cure
+    {
+        GardeningSprayEmpty,
+
+        Result:GardeningSprayMilk,
+        Time:40.0,
+        Category:Farming,
+    }
+}";
+
+        let module = crate::parse_file::<nom::error::Error<&str>>(module_text)
+            .expect("failed to parse module")
+            .remove(0);
+        let names: Vec<&str> = module.definitions.iter().map(Definition::name).collect();
+
+        assert_eq!(vec!["RedRadish", "Make Mildew Cure"], names);
+    }
+
+    #[test]
+    fn doc_comments_survive_display() {
+        let recipe_text = "// Cures mildew on plants.
+// Needs a spray bottle.
+recipe Make Mildew Cure
+{
+    GardeningSprayEmpty,
+
+    Result:GardeningSprayMilk,
+    Time:40.0,
+    Category:Farming,
+}";
+        let options = ParseOptions {
+            attach_comments: true,
+            ..ParseOptions::default()
+        };
+        let definition_res: Result<Definition> = definition(options)(recipe_text);
+        let (_, Definition::Recipe(expected)) = definition_res.expect("failed to parse definition")
+        else {
+            panic!("expected a recipe");
+        };
+        assert_eq!(
+            &[
+                String::from("Cures mildew on plants."),
+                String::from("Needs a spray bottle.")
+            ],
+            expected.leading_comments()
+        );
+
+        let text = expected.to_string();
+        let reparsed: IResult<&str, Definition, nom::error::Error<&str>> =
+            definition(options)(&text);
+        let (_, actual) = reparsed.expect("failed to parse displayed recipe");
+
+        assert_eq!(Definition::Recipe(expected), actual);
+    }
+}
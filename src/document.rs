@@ -0,0 +1,239 @@
+use std::ops::Range;
+
+use nom::{
+    branch::alt,
+    character::complete::{alphanumeric1, multispace0, multispace1},
+    combinator::recognize,
+    multi::many0,
+};
+
+use crate::{block::string_with_spaces_delimited_by_open_brace, comment};
+
+type Error<'a> = nom::error::Error<&'a str>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SymbolKind {
+    Module,
+    Recipe,
+    Item,
+    /// A named block of a type this crate doesn't model, such as `fixing`.
+    Other(String),
+}
+
+impl From<&str> for SymbolKind {
+    fn from(block_tag: &str) -> Self {
+        match block_tag {
+            "module" => SymbolKind::Module,
+            "recipe" => SymbolKind::Recipe,
+            "item" => SymbolKind::Item,
+            other => SymbolKind::Other(other.to_string()),
+        }
+    }
+}
+
+/// A named block in a document. Ranges are byte offsets into the parsed
+/// text; `full_range` runs from the block tag to the closing brace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Symbol {
+    pub kind: SymbolKind,
+    pub name: String,
+    pub name_range: Range<usize>,
+    pub full_range: Range<usize>,
+}
+
+/// The outline of a script file, for editor integration.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Document {
+    /// Every named block, in the order they start.
+    pub symbols: Vec<Symbol>,
+    /// Where the outline stopped if the text could not be read to the end.
+    pub unparsed: Option<usize>,
+}
+
+/// Outline a whole file without interpreting block bodies. Only the block
+/// structure needs to be valid: unknown block types and bodies that the
+/// typed parsers would reject still produce symbols.
+pub fn parse_document(input: &str) -> Document {
+    let mut document = Document::default();
+    let mut rest = skip_trivia(input);
+    while !rest.is_empty() {
+        match block(input, rest, &mut document.symbols) {
+            Some(after) => rest = skip_trivia(after),
+            None => {
+                document.unparsed = Some(offset(input, rest));
+                break;
+            }
+        }
+    }
+    document
+}
+
+fn offset(document: &str, rest: &str) -> usize {
+    document.len() - rest.len()
+}
+
+fn skip_trivia(input: &str) -> &str {
+    match recognize(many0(alt((multispace1, comment))))(input) {
+        Ok((rest, _)) => rest,
+        Err::<_, nom::Err<Error>>(_) => input,
+    }
+}
+
+/// Read one block starting at `input`, recording it and any nested module
+/// definitions in `symbols`. Returns the text after the closing brace.
+fn block<'a>(document: &str, input: &'a str, symbols: &mut Vec<Symbol>) -> Option<&'a str> {
+    let start = offset(document, input);
+    let (rest, block_tag) = alphanumeric1::<_, Error>(input).ok()?;
+    let (rest, _) = multispace1::<_, Error>(rest).ok()?;
+
+    if let Some(body) = rest.strip_prefix('{') {
+        return skip_body(body);
+    }
+
+    let name_start = offset(document, rest);
+    let (rest, name) = string_with_spaces_delimited_by_open_brace::<Error>(rest).ok()?;
+    let name_range = name_start..name_start + name.len();
+    let (rest, _) = multispace0::<_, Error>(rest).ok()?;
+    let rest = rest.strip_prefix('{')?;
+
+    let kind = SymbolKind::from(block_tag);
+    let index = symbols.len();
+    symbols.push(Symbol {
+        kind: kind.clone(),
+        name: name.to_string(),
+        name_range,
+        full_range: start..start,
+    });
+
+    let after = if kind == SymbolKind::Module {
+        module_body(document, rest, symbols)
+    } else {
+        skip_body(rest)
+    };
+    let Some(after) = after else {
+        // Without a closing brace the block has no extent, so neither it
+        // nor anything found inside it is reported.
+        symbols.truncate(index);
+        return None;
+    };
+    symbols[index].full_range = start..offset(document, after);
+    Some(after)
+}
+
+fn module_body<'a>(document: &str, input: &'a str, symbols: &mut Vec<Symbol>) -> Option<&'a str> {
+    let mut rest = skip_trivia(input);
+    loop {
+        if let Some(after) = rest.strip_prefix('}') {
+            return Some(after);
+        }
+        rest = skip_trivia(block(document, rest, symbols)?);
+    }
+}
+
+/// Skip to just past the brace that closes a block whose opening brace has
+/// already been consumed.
+pub(crate) fn skip_body(input: &str) -> Option<&str> {
+    let mut depth = 1;
+    for (i, c) in input.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&input[i + 1..]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symbol_ranges_for_module_with_recipe() {
+        let text = "module Base
+{
+  recipe Make Mildew Cure
+  {
+    GardeningSprayEmpty,
+
+    Result:GardeningSprayMilk,
+    Time:40.0,
+    Category:Farming,
+    NeedToBeLearn:true,
+  }
+}
+";
+        let recipe_start = text.find("recipe").unwrap();
+        let recipe_end = text.rfind("  }").unwrap() + 3;
+        let name_start = text.find("Make").unwrap();
+        let expected = Document {
+            symbols: vec![
+                Symbol {
+                    kind: SymbolKind::Module,
+                    name: String::from("Base"),
+                    name_range: 7..11,
+                    full_range: 0..text.trim_end().len(),
+                },
+                Symbol {
+                    kind: SymbolKind::Recipe,
+                    name: String::from("Make Mildew Cure"),
+                    name_range: name_start..name_start + "Make Mildew Cure".len(),
+                    full_range: recipe_start..recipe_end,
+                },
+            ],
+            unparsed: None,
+        };
+
+        let actual = parse_document(text);
+
+        assert_eq!(expected, actual);
+        assert_eq!(
+            "Make Mildew Cure",
+            &text[actual.symbols[1].name_range.clone()]
+        );
+    }
+
+    #[test]
+    fn unknown_blocks_are_kept_as_raw_symbols() {
+        let text = "module Base
+{
+  imports { Farming }
+
+  /* not modelled */
+  fixing Fix Pot
+  {
+    Require : Pot,
+    Fixer : Scotchtape=2,
+  }
+}
+";
+
+        let actual = parse_document(text);
+
+        assert_eq!(None, actual.unparsed);
+        assert_eq!(2, actual.symbols.len());
+        assert_eq!(
+            SymbolKind::Other(String::from("fixing")),
+            actual.symbols[1].kind
+        );
+        assert_eq!("Fix Pot", actual.symbols[1].name);
+    }
+
+    #[test]
+    fn unclosed_block_stops_the_outline() {
+        let text = "module Base\n{\n}\n\nmodule Farming\n{\n  recipe Broken\n  {\n";
+
+        let actual = parse_document(text);
+
+        assert_eq!(text.find("module Farming"), actual.unparsed);
+        assert_eq!(1, actual.symbols.len());
+        assert_eq!("Base", actual.symbols[0].name);
+    }
+}
@@ -0,0 +1,851 @@
+use std::{
+    error::Error,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{alphanumeric1, multispace0, multispace1, u32},
+    combinator::{all_consuming, cond, map, opt, verify},
+    error::{ContextError, ErrorKind, ParseError},
+    multi::separated_list0,
+    sequence::{delimited, pair, preceded, terminated, tuple},
+    IResult,
+};
+
+use crate::{
+    block::string_with_spaces_delimited_by_open_brace, definition, document::skip_body,
+    field_value_nl, identifier1, leading_comments, named_block_with_options, separator,
+    trailing_comments, unnamed_block_repeated, whitespace0, Definition, ModuleBlock, ParseOptions,
+};
+
+fn module_block<'a, E>(
+    options: ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, ModuleBlock<Definition>, E>
+where
+    E: ParseError<&'a str> + ContextError<&'a str>,
+{
+    map(module_block_skipping(options, false), |(block, _)| block)
+}
+
+/// A module, along with how many blocks in it were skipped. Blocks of an
+/// unknown type are only skipped when `skip_unknown` is set; otherwise
+/// they fail the parse as usual.
+fn module_block_skipping<'a, E>(
+    options: ParseOptions,
+    skip_unknown: bool,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (ModuleBlock<Definition>, usize), E>
+where
+    E: ParseError<&'a str> + ContextError<&'a str>,
+{
+    move |input: &'a str| {
+        // Only log once the `module` tag and name have matched, so that
+        // failed attempts and bare definitions aren't reported as modules.
+        #[cfg(feature = "logging")]
+        if let Ok((_, name)) = module_name::<()>(options)(input) {
+            trace!("entering module {}", name);
+        }
+        let entry = |input: &'a str| {
+            if skip_unknown {
+                alt((
+                    map(definition(options), Some),
+                    map(
+                        preceded(
+                            cond(options.attach_comments, leading_comments(options)),
+                            unknown_block,
+                        ),
+                        |_| None,
+                    ),
+                ))(input)
+            } else {
+                map(definition(options), Some)(input)
+            }
+        };
+        let (input, (name, (imports, version, entries))) = named_block_with_options(
+            "module",
+            options,
+            delimited(
+                cond(!options.attach_comments, leading_comments(options)),
+                tuple((
+                    opt(terminated(imports, definition_separator(options))),
+                    opt(terminated(
+                        field_value_nl("VERSION", "=", u32),
+                        definition_separator(options),
+                    )),
+                    separated_list0(definition_separator(options), entry),
+                )),
+                trailing_comments(options),
+            ),
+        )(input)?;
+
+        let skipped = entries.iter().filter(|entry| entry.is_none()).count();
+        let definitions = entries.into_iter().flatten().collect();
+        let mut block = ModuleBlock::from((name, definitions));
+        for definition in &mut block.definitions {
+            if let Definition::Recipe(recipe) = definition {
+                recipe.set_module(block.name.as_str());
+            }
+        }
+        block.imports = imports
+            .unwrap_or_default()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        block.version = version;
+        trace!("leaving module {}", block.name);
+        Ok((input, (block, skipped)))
+    }
+}
+
+/// The name from a module's header, without consuming anything.
+#[cfg(feature = "logging")]
+fn module_name<'a, E>(options: ParseOptions) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str, E>
+where
+    E: ParseError<&'a str>,
+{
+    use nom::combinator::peek;
+
+    use crate::comment::inline_separator;
+
+    peek(preceded(
+        pair(tag("module"), inline_separator(options)),
+        string_with_spaces_delimited_by_open_brace,
+    ))
+}
+
+/// The block types the typed parsers understand. Anything else inside a
+/// module can be skipped by `unknown_block`.
+const KNOWN_BLOCKS: &[&str] = &["module", "recipe", "item", "option"];
+
+/// A named block of a type the typed parsers don't model, such as
+/// `fixing`, read only as far as its closing brace.
+fn unknown_block<'a, E>(input: &'a str) -> IResult<&'a str, &'a str, E>
+where
+    E: ParseError<&'a str>,
+{
+    let (rest, _) = verify(alphanumeric1, |block_tag: &str| {
+        !KNOWN_BLOCKS.contains(&block_tag)
+    })(input)?;
+    let (rest, _) = multispace1(rest)?;
+    let (rest, _) = string_with_spaces_delimited_by_open_brace(rest)?;
+    let (rest, _) = pair(multispace0, tag("{"))(rest)?;
+    match skip_body(rest) {
+        Some(after) => Ok((after, &input[..input.len() - after.len()])),
+        None => Err(nom::Err::Error(E::from_error_kind(rest, ErrorKind::Char))),
+    }
+}
+
+/// The modules named in an `imports { Base }` block. Commas after the
+/// names are tolerated.
+fn imports<'a, E>(input: &'a str) -> IResult<&'a str, Vec<&'a str>, E>
+where
+    E: ParseError<&'a str>,
+{
+    unnamed_block_repeated("imports", terminated(identifier1, opt(tag(","))))(input)
+}
+
+/// A summary of what a file parse produced. Blocks of a type the typed
+/// parsers don't model, such as `fixing`, are skipped rather than rejected
+/// and counted in `unknown_blocks`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ParseStats {
+    pub modules: usize,
+    pub recipes: usize,
+    pub items: usize,
+    pub options: usize,
+    pub unknown_blocks: usize,
+}
+
+impl ParseStats {
+    fn count(&mut self, module: &ModuleBlock<Definition>, unknown_blocks: usize) {
+        self.modules += 1;
+        self.unknown_blocks += unknown_blocks;
+        for definition in &module.definitions {
+            match definition {
+                Definition::Recipe(_) => self.recipes += 1,
+                Definition::Item(_) => self.items += 1,
+                Definition::Option(_) => self.options += 1,
+            }
+        }
+    }
+}
+
+/// Failure to read and parse a script from a stream.
+#[derive(Debug)]
+pub enum ReadError {
+    Io(io::Error),
+    Parse(nom::Err<nom::error::Error<String>>),
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io(err) => write!(f, "failed to read script: {}", err),
+            ReadError::Parse(err) => write!(f, "failed to parse script: {}", err),
+        }
+    }
+}
+
+impl Error for ReadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReadError::Io(err) => Some(err),
+            ReadError::Parse(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(err: io::Error) -> Self {
+        ReadError::Io(err)
+    }
+}
+
+/// Whitespace between two definitions. Comments are left in place when
+/// they are going to be attached to the definition that follows.
+fn definition_separator<'a, E>(
+    options: ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str, E>
+where
+    E: ParseError<&'a str>,
+{
+    move |input: &'a str| {
+        if options.attach_comments {
+            multispace1(input)
+        } else {
+            separator(options)(input)
+        }
+    }
+}
+
+/// Parse the next module in `input`, returning whatever follows it
+/// unparsed so that a caller can work through a file one module at a time.
+pub fn parse_one_module<'a, E>(input: &'a str) -> IResult<&'a str, ModuleBlock<Definition>, E>
+where
+    E: ParseError<&'a str> + ContextError<&'a str>,
+{
+    let options = ParseOptions::default();
+    preceded(whitespace0(options), module_block(options))(input)
+}
+
+/// Parse a whole script file. The entire input must be consumed; an empty
+/// or whitespace-only file parses to no modules.
+pub fn parse_file<'a, E>(input: &'a str) -> Result<Vec<ModuleBlock<Definition>>, nom::Err<E>>
+where
+    E: ParseError<&'a str> + ContextError<&'a str>,
+{
+    parse_file_with_options(input, ParseOptions::default())
+}
+
+pub fn parse_file_with_options<'a, E>(
+    input: &'a str,
+    options: ParseOptions,
+) -> Result<Vec<ModuleBlock<Definition>>, nom::Err<E>>
+where
+    E: ParseError<&'a str> + ContextError<&'a str>,
+{
+    let (_, modules) = all_consuming(preceded(
+        whitespace0(options),
+        terminated(
+            separated_list0(separator(options), module_block(options)),
+            whitespace0(options),
+        ),
+    ))(input)?;
+    Ok(modules)
+}
+
+/// Like `parse_file_with_options`, also returning counts of what was
+/// parsed. Blocks of unknown types inside a module are skipped and counted
+/// instead of failing the parse.
+pub fn parse_file_with_stats<'a, E>(
+    input: &'a str,
+    options: ParseOptions,
+) -> Result<(Vec<ModuleBlock<Definition>>, ParseStats), nom::Err<E>>
+where
+    E: ParseError<&'a str> + ContextError<&'a str>,
+{
+    let (_, parsed) = all_consuming(preceded(
+        whitespace0(options),
+        terminated(
+            separated_list0(separator(options), module_block_skipping(options, true)),
+            whitespace0(options),
+        ),
+    ))(input)?;
+
+    let mut stats = ParseStats::default();
+    let mut modules = Vec::with_capacity(parsed.len());
+    for (module, unknown_blocks) in parsed {
+        stats.count(&module, unknown_blocks);
+        modules.push(module);
+    }
+    Ok((modules, stats))
+}
+
+/// Parse a file whose top level mixes modules with bare definitions, as
+/// some mods write them. Definitions inside modules are flattened into the
+/// list in source order, and the module names are dropped.
+pub fn parse_document_flat<'a, E>(input: &'a str) -> Result<Vec<Definition>, nom::Err<E>>
+where
+    E: ParseError<&'a str> + ContextError<&'a str>,
+{
+    let options = ParseOptions::default();
+    let (_, groups) = all_consuming(preceded(
+        whitespace0(options),
+        terminated(
+            separated_list0(
+                separator(options),
+                alt((
+                    map(module_block(options), |module| module.definitions),
+                    map(definition(options), |definition| vec![definition]),
+                )),
+            ),
+            whitespace0(options),
+        ),
+    ))(input)?;
+    Ok(groups.into_iter().flatten().collect())
+}
+
+/// Read a whole script from `reader` and parse it. The parsers need the
+/// complete input, so the stream is read to the end before parsing.
+pub fn parse_reader(
+    mut reader: impl io::BufRead,
+) -> Result<Vec<ModuleBlock<Definition>>, ReadError> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    parse_file::<nom::error::Error<&str>>(&input).map_err(|err| ReadError::Parse(err.to_owned()))
+}
+
+/// The outcome of parsing one file of a directory.
+pub type FileResult = Result<Vec<ModuleBlock<Definition>>, ReadError>;
+
+/// Parse every `.txt` file under `path`, descending into subdirectories,
+/// in sorted path order. A file that can't be read or parsed gets its
+/// error alongside its path rather than stopping the walk; only a failure
+/// to list a directory is returned as an error. Symlinked directories are
+/// not followed.
+pub fn parse_dir(path: impl AsRef<Path>) -> io::Result<Vec<(PathBuf, FileResult)>> {
+    let mut results = Vec::new();
+    parse_dir_into(path.as_ref(), &mut results)?;
+    Ok(results)
+}
+
+fn parse_dir_into(dir: &Path, results: &mut Vec<(PathBuf, FileResult)>) -> io::Result<()> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| {
+            let entry = entry?;
+            Ok((entry.path(), entry.file_type()?))
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (path, file_type) in entries {
+        // `file_type` doesn't follow symlinks, so a linked directory is
+        // never entered and a link loop can't recurse forever.
+        if file_type.is_dir() {
+            parse_dir_into(&path, results)?;
+        } else if path.extension().is_some_and(|ext| ext == "txt") {
+            let parsed = fs::File::open(&path)
+                .map_err(ReadError::from)
+                .and_then(|file| parse_reader(io::BufReader::new(file)));
+            results.push((path, parsed));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Recipe;
+
+    type Error = nom::error::Error<&'static str>;
+
+    const MODULE_TEXT: &str = "
+module Base
+{
+  recipe Make Mildew Cure
+  {
+    GardeningSprayEmpty,
+    Base.Milk,
+
+    Result:GardeningSprayMilk,
+    Time:40.0,
+    Category:Farming,
+    NeedToBeLearn:true,
+  }
+}
+";
+
+    /// The recipe in `MODULE_TEXT`, as parsed from within `module Base`.
+    fn mildew_cure() -> Recipe {
+        let mut recipe = Recipe::new(
+            "Make Mildew Cure",
+            vec!["GardeningSprayEmpty", "Base.Milk"],
+            "GardeningSprayMilk",
+            40.0,
+            "Farming",
+            true,
+        );
+        recipe.set_module("Base");
+        recipe
+    }
+
+    #[test]
+    fn parse_file_of_one_module() {
+        let expected = vec![ModuleBlock::new(
+            "Base",
+            vec![Definition::Recipe(Box::new(mildew_cure()))],
+        )];
+
+        let actual = parse_file::<Error>(MODULE_TEXT).expect("failed to parse file");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn parse_file_with_comments_requires_option() {
+        let file_text = format!("/* Base recipes */\n{MODULE_TEXT}// end of file\n");
+        let file_text = file_text.as_str();
+        let expected = vec![ModuleBlock::new(
+            "Base",
+            vec![Definition::Recipe(Box::new(mildew_cure()))],
+        )];
+
+        assert!(parse_file::<nom::error::Error<&str>>(file_text).is_err());
+
+        let options = ParseOptions {
+            allow_comments: true,
+            ..ParseOptions::default()
+        };
+        let actual = parse_file_with_options::<nom::error::Error<&str>>(file_text, options)
+            .expect("failed to parse file");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn trailing_comment_after_last_module() {
+        let file_text = format!("{}\n// trailing note\n", MODULE_TEXT.trim_end());
+        let options = ParseOptions {
+            allow_comments: true,
+            ..ParseOptions::default()
+        };
+
+        let actual = parse_file_with_options::<nom::error::Error<&str>>(&file_text, options)
+            .expect("failed to parse file");
+
+        assert_eq!(
+            vec![ModuleBlock::new(
+                "Base",
+                vec![Definition::Recipe(Box::new(mildew_cure()))]
+            )],
+            actual
+        );
+    }
+
+    #[test]
+    fn attach_comment_to_following_recipe() {
+        let file_text = "
+module Base
+{
+  /* Cures mildew on plants */
+  recipe Make Mildew Cure
+  {
+    GardeningSprayEmpty,
+    Base.Milk,
+
+    Result:GardeningSprayMilk,
+    Time:40.0,
+    Category:Farming,
+    NeedToBeLearn:true,
+  }
+}
+";
+        let options = ParseOptions {
+            attach_comments: true,
+            ..ParseOptions::default()
+        };
+        let actual =
+            parse_file_with_options::<Error>(file_text, options).expect("failed to parse file");
+
+        let Definition::Recipe(recipe) = &actual[0].definitions[0] else {
+            panic!("expected a recipe");
+        };
+        assert_eq!(
+            &[String::from("Cures mildew on plants")],
+            recipe.leading_comments()
+        );
+    }
+
+    #[test]
+    fn parse_one_module_leaves_the_rest() {
+        let file_text = "
+module Base
+{
+  recipe Make Mildew Cure
+  {
+    GardeningSprayEmpty,
+
+    Result:GardeningSprayMilk,
+    Time:40.0,
+    Category:Farming,
+    NeedToBeLearn:true,
+  }
+}
+
+module Farming
+{
+  item RedRadish
+  {
+    Type = Food
+  }
+}
+";
+
+        let (rest, first) = parse_one_module::<Error>(file_text).expect("failed to parse module");
+        assert_eq!("Base", first.name);
+        assert!(rest.trim_start().starts_with("module Farming"));
+
+        let (rest, second) = parse_one_module::<Error>(rest).expect("failed to parse module");
+        assert_eq!("Farming", second.name);
+        assert_eq!("\n", rest);
+    }
+
+    #[test]
+    fn count_modules_and_definitions() {
+        let file_text = "
+module Base
+{
+  recipe Make Mildew Cure
+  {
+    GardeningSprayEmpty,
+
+    Result:GardeningSprayMilk,
+    Time:40.0,
+    Category:Farming,
+  }
+
+  recipe Make Flies Cure
+  {
+    GardeningSprayEmpty,
+
+    Result:GardeningSprayCigarettes,
+    Time:40.0,
+    Category:Farming,
+  }
+}
+
+module Farming
+{
+  item RedRadish
+  {
+    Type = Food
+  }
+
+  fixing Fix Shovel
+  {
+    Require : Shovel,
+    Fixer : Woodglue=2,
+  }
+
+  recipe Open Seed Packet
+  {
+    RadishBagSeed,
+
+    Result:RadishSeed=50,
+    Time:20.0,
+    Category:Farming,
+  }
+}
+";
+        let expected = ParseStats {
+            modules: 2,
+            recipes: 3,
+            items: 1,
+            options: 0,
+            unknown_blocks: 1,
+        };
+
+        let (_, actual) = parse_file_with_stats::<Error>(file_text, ParseOptions::default())
+            .expect("failed to parse file");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn count_option_definitions() {
+        let file_text = "
+module Farming
+{
+  option Farming.Speed
+  {
+    type = integer,
+    default = 2,
+    page = Farming,
+  }
+
+  item RedRadish
+  {
+    Type = Food
+  }
+}
+";
+        let expected = ParseStats {
+            modules: 1,
+            recipes: 0,
+            items: 1,
+            options: 1,
+            unknown_blocks: 0,
+        };
+
+        let (_, actual) = parse_file_with_stats::<Error>(file_text, ParseOptions::default())
+            .expect("failed to parse file");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn dotted_module_name_is_kept_verbatim() {
+        let file_text = "
+module My.Big.Mod {
+  item RedRadish
+  {
+    Type = Food
+  }
+}
+";
+
+        let actual = parse_file::<Error>(file_text).expect("failed to parse file");
+
+        assert_eq!("My.Big.Mod", actual[0].name);
+    }
+
+    #[test]
+    fn parse_module_version() {
+        let file_text = "
+module Base
+{
+  VERSION = 2,
+
+  recipe Make Mildew Cure
+  {
+    GardeningSprayEmpty,
+    Base.Milk,
+
+    Result:GardeningSprayMilk,
+    Time:40.0,
+    Category:Farming,
+    NeedToBeLearn:true,
+  }
+}
+";
+        let expected =
+            vec![
+                ModuleBlock::new("Base", vec![Definition::Recipe(Box::new(mildew_cure()))])
+                    .with_version(2),
+            ];
+
+        let actual = parse_file::<Error>(file_text).expect("failed to parse file");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn imports_directly_before_first_definition() {
+        let file_text = "
+module Farming
+{
+  imports { Base }
+  recipe Make Mildew Cure
+  {
+    GardeningSprayEmpty,
+    Base.Milk,
+
+    Result:GardeningSprayMilk,
+    Time:40.0,
+    Category:Farming,
+    NeedToBeLearn:true,
+  }
+}
+";
+        let mut recipe = mildew_cure();
+        recipe.set_module("Farming");
+        let mut expected = ModuleBlock::new("Farming", vec![Definition::Recipe(Box::new(recipe))]);
+        expected.imports = vec![String::from("Base")];
+
+        let actual = parse_file::<Error>(file_text).expect("failed to parse file");
+
+        assert_eq!(vec![expected], actual);
+    }
+
+    #[test]
+    fn recipes_record_their_module() {
+        let modules = parse_file::<Error>(MODULE_TEXT).expect("failed to parse file");
+        let Definition::Recipe(recipe) = &modules[0].definitions[0] else {
+            panic!("expected a recipe");
+        };
+
+        assert_eq!(Some("Base"), recipe.module());
+    }
+
+    #[test]
+    fn empty_file_has_no_modules() {
+        for file_text in ["", " \n\t\n"] {
+            let actual = parse_file::<Error>(file_text).expect("failed to parse empty file");
+
+            assert!(actual.is_empty());
+        }
+    }
+
+    #[test]
+    fn flatten_bare_recipe_and_module() {
+        let file_text = format!(
+            "recipe Make Cake Batter
+{{
+    Flour,
+
+    Result:CakeBatter,
+    Time:50.0,
+    Category:Cooking,
+}}
+{MODULE_TEXT}"
+        );
+        let cake_batter = Recipe::new(
+            "Make Cake Batter",
+            vec!["Flour"],
+            "CakeBatter",
+            50.0,
+            "Cooking",
+            false,
+        );
+        let expected = vec![
+            Definition::Recipe(Box::new(cake_batter)),
+            Definition::Recipe(Box::new(mildew_cure())),
+        ];
+
+        let actual = parse_document_flat::<nom::error::Error<&str>>(&file_text)
+            .expect("failed to parse file");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[cfg(feature = "logging")]
+    #[test]
+    fn trace_block_boundaries() {
+        use std::sync::Mutex;
+
+        struct CaptureLogger(Mutex<Vec<String>>);
+
+        impl log::Log for CaptureLogger {
+            fn enabled(&self, _: &log::Metadata) -> bool {
+                true
+            }
+
+            fn log(&self, record: &log::Record) {
+                self.0.lock().unwrap().push(record.args().to_string());
+            }
+
+            fn flush(&self) {}
+        }
+
+        static LOGGER: CaptureLogger = CaptureLogger(Mutex::new(Vec::new()));
+        log::set_logger(&LOGGER).expect("logger already set");
+        log::set_max_level(log::LevelFilter::Trace);
+
+        let file_text = format!(
+            "{}\n{}",
+            MODULE_TEXT.replace("module Base", "module Traced"),
+            MODULE_TEXT.replace("module Base", "module Logged")
+        );
+        parse_file::<nom::error::Error<&str>>(&file_text).expect("failed to parse file");
+
+        let events = LOGGER.0.lock().unwrap();
+        let position = |event: &str| {
+            events
+                .iter()
+                .position(|e| e == event)
+                .unwrap_or_else(|| panic!("missing trace event {event:?}"))
+        };
+        let entered_traced = position("entering module Traced");
+        let left_traced = position("leaving module Traced");
+        let entered_logged = position("entering module Logged");
+        let left_logged = position("leaving module Logged");
+        assert!(entered_traced < left_traced);
+        assert!(left_traced < entered_logged);
+        assert!(entered_logged < left_logged);
+        assert!(events.iter().any(|e| e == "parsed recipe Make Mildew Cure"));
+        drop(events);
+
+        LOGGER.0.lock().unwrap().clear();
+        let bare_recipe =
+            "recipe Bare\n{\n  Nails,\n\n  Result:Plank,\n  Time:10.0,\n  Category:Carpentry,\n}";
+        parse_document_flat::<nom::error::Error<&str>>(bare_recipe).expect("failed to parse file");
+
+        let events = LOGGER.0.lock().unwrap();
+        assert!(!events.iter().any(|e| e.starts_with("entering")));
+    }
+
+    #[test]
+    fn parse_from_reader() {
+        let expected = vec![ModuleBlock::new(
+            "Base",
+            vec![Definition::Recipe(Box::new(mildew_cure()))],
+        )];
+
+        let actual = parse_reader(io::Cursor::new(MODULE_TEXT)).expect("failed to parse reader");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn parse_dir_keeps_per_file_errors() {
+        let dir = std::env::temp_dir().join(format!("pz-data-parse-dir-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("failed to create temp dir");
+        fs::write(dir.join("broken.txt"), "module Base {").expect("failed to write file");
+        fs::write(dir.join("recipes.txt"), MODULE_TEXT).expect("failed to write file");
+        fs::write(dir.join("notes.md"), "not a script").expect("failed to write file");
+
+        let actual = parse_dir(&dir);
+        fs::remove_dir_all(&dir).expect("failed to remove temp dir");
+        let actual = actual.expect("failed to read dir");
+
+        assert_eq!(2, actual.len());
+        assert_eq!(dir.join("broken.txt"), actual[0].0);
+        assert!(matches!(actual[0].1, Err(ReadError::Parse(_))));
+        assert_eq!(dir.join("recipes.txt"), actual[1].0);
+        let modules = actual[1].1.as_ref().expect("failed to parse valid file");
+        assert_eq!(
+            &vec![ModuleBlock::new(
+                "Base",
+                vec![Definition::Recipe(Box::new(mildew_cure()))]
+            )],
+            modules
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn parse_dir_skips_symlink_loops() {
+        let dir = std::env::temp_dir().join(format!("pz-data-symlink-{}", std::process::id()));
+        fs::create_dir_all(dir.join("mods")).expect("failed to create temp dir");
+        fs::write(dir.join("mods/recipes.txt"), MODULE_TEXT).expect("failed to write file");
+        std::os::unix::fs::symlink(&dir, dir.join("mods/loop")).expect("failed to create link");
+
+        let actual = parse_dir(&dir);
+        fs::remove_dir_all(&dir).expect("failed to remove temp dir");
+        let actual = actual.expect("failed to read dir");
+
+        assert_eq!(1, actual.len());
+        assert_eq!(dir.join("mods/recipes.txt"), actual[0].0);
+    }
+
+    #[test]
+    fn parse_reader_reports_parse_errors() {
+        let actual = parse_reader(io::Cursor::new("module Base {"));
+
+        assert!(matches!(actual, Err(ReadError::Parse(_))));
+    }
+}
@@ -0,0 +1,125 @@
+use std::fmt;
+
+use nom::{
+    bytes::complete::tag,
+    character::complete::{space0, u32},
+    combinator::{map, opt},
+    error::ParseError,
+    sequence::{delimited, pair, preceded},
+    IResult,
+};
+
+use crate::{identifier1, list_value};
+
+/// The value of a `fixing` block's `Fixer` field: the item used to make
+/// the repair, then the `Name=count` requirements it needs, as in
+/// `Fixer:Woodglue=2;Woodwork=2,`. The item and each requirement are
+/// parsed the same way, as a name and a count that defaults to 1.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Fixer {
+    pub item: String,
+    pub count: u32,
+    pub skill_reqs: Vec<(String, u32)>,
+}
+
+impl Fixer {
+    pub fn new(item: impl Into<String>, count: u32, skill_reqs: Vec<(String, u32)>) -> Self {
+        Self {
+            item: item.into(),
+            count,
+            skill_reqs,
+        }
+    }
+}
+
+impl fmt::Display for Fixer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.item, self.count)?;
+        for (name, count) in &self.skill_reqs {
+            write!(f, ";{}={}", name, count)?;
+        }
+        Ok(())
+    }
+}
+
+fn requirement<'a, E>(input: &'a str) -> IResult<&'a str, (String, u32), E>
+where
+    E: ParseError<&'a str>,
+{
+    map(
+        delimited(
+            space0,
+            pair(identifier1, opt(preceded(tag("="), u32))),
+            space0,
+        ),
+        |(name, count)| (name.to_string(), count.unwrap_or(1)),
+    )(input)
+}
+
+pub fn fixer<'a, E>(input: &'a str) -> IResult<&'a str, Fixer, E>
+where
+    E: ParseError<&'a str>,
+{
+    map(
+        pair(
+            requirement,
+            opt(preceded(tag(";"), list_value(requirement))),
+        ),
+        |((item, count), skill_reqs)| Fixer::new(item, count, skill_reqs.unwrap_or_default()),
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Result<T> = IResult<&'static str, T, nom::error::Error<&'static str>>;
+
+    #[test]
+    fn parse_fixer_with_skill_requirements() {
+        let fixer_res: Result<Fixer> = fixer("Woodglue=2; Woodwork=2; Carpentry=1,");
+        let (rest, actual) = fixer_res.expect("failed to parse fixer");
+
+        assert_eq!(",", rest);
+        assert_eq!(
+            Fixer::new(
+                "Woodglue",
+                2,
+                vec![
+                    (String::from("Woodwork"), 2),
+                    (String::from("Carpentry"), 1)
+                ]
+            ),
+            actual
+        );
+        assert_eq!("Woodglue=2;Woodwork=2;Carpentry=1", actual.to_string());
+    }
+
+    #[test]
+    fn parse_bare_fixer() {
+        let fixer_res: Result<Fixer> = fixer("DuctTape,");
+        let (_, actual) = fixer_res.expect("failed to parse fixer");
+
+        assert_eq!(Fixer::new("DuctTape", 1, vec![]), actual);
+    }
+
+    #[test]
+    fn fixer_display_round_trips() {
+        let fixer_text = "Glue=2;DuctTape=1";
+        let fixer_res: Result<Fixer> = fixer(fixer_text);
+        let (_, actual) = fixer_res.expect("failed to parse fixer");
+
+        assert_eq!(
+            Fixer::new("Glue", 2, vec![(String::from("DuctTape"), 1)]),
+            actual
+        );
+
+        let displayed = actual.to_string();
+        let reparsed: IResult<&str, Fixer, nom::error::Error<&str>> = fixer(&displayed);
+
+        assert_eq!(fixer_text, displayed);
+        assert_eq!(Ok(("", actual)), reparsed);
+    }
+}
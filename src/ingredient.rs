@@ -0,0 +1,296 @@
+use std::fmt;
+
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{line_ending, multispace0, space0, space1},
+    combinator::{map, opt},
+    error::ParseError,
+    multi::many0,
+    number::complete::float,
+    sequence::{delimited, pair, preceded, separated_pair, tuple},
+    IResult,
+};
+
+use crate::{amount, escaped_identifier, identifier1, Amount};
+
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Ingredient {
+    Item {
+        name: String,
+        count: Amount,
+    },
+    /// Any item carrying the tag, written `[Hammer]`.
+    Tag(String),
+    /// An item-type mapper, written `mapper:bucketEmpty`, which picks the
+    /// item from a table defined elsewhere in the script.
+    Mapper(String),
+    /// A quantity of fluid, written `fluid:Water=0.5`.
+    Fluid {
+        name: String,
+        amount: f32,
+    },
+}
+
+impl Ingredient {
+    pub fn new(name: impl Into<String>, count: u32) -> Self {
+        Ingredient::with_amount(name, Amount::Int(count))
+    }
+
+    /// An ingredient measured by `amount`, which may be fractional.
+    pub fn with_amount(name: impl Into<String>, amount: Amount) -> Self {
+        Ingredient::Item {
+            name: name.into(),
+            count: amount,
+        }
+    }
+
+    pub fn tag(tag: impl Into<String>) -> Self {
+        Ingredient::Tag(tag.into())
+    }
+
+    pub fn mapper(mapper: impl Into<String>) -> Self {
+        Ingredient::Mapper(mapper.into())
+    }
+
+    pub fn fluid(name: impl Into<String>, amount: f32) -> Self {
+        Ingredient::Fluid {
+            name: name.into(),
+            amount,
+        }
+    }
+
+    /// The item or fluid name, or the tag or mapper name for selectors.
+    pub fn name(&self) -> &str {
+        match self {
+            Ingredient::Item { name, .. } => name,
+            Ingredient::Tag(tag) => tag,
+            Ingredient::Mapper(mapper) => mapper,
+            Ingredient::Fluid { name, .. } => name,
+        }
+    }
+
+    pub fn is_tag(&self) -> bool {
+        matches!(self, Ingredient::Tag(_))
+    }
+
+    /// Whether this names a specific item, rather than selecting one by tag
+    /// or mapper or being a fluid.
+    pub fn is_item(&self) -> bool {
+        matches!(self, Ingredient::Item { .. })
+    }
+
+    /// Split a module-qualified name like `Base.Milk` into its module and
+    /// item parts. Bare names, tags, mappers and fluids have no module.
+    pub fn qualified_name(&self) -> (Option<&str>, &str) {
+        match self {
+            Ingredient::Item { name, .. } => match name.rsplit_once('.') {
+                Some((module, item)) => (Some(module), item),
+                None => (None, name),
+            },
+            Ingredient::Tag(tag) => (None, tag),
+            Ingredient::Mapper(mapper) => (None, mapper),
+            Ingredient::Fluid { name, .. } => (None, name),
+        }
+    }
+
+    /// How many are needed, or how much for a fluid. Tag and mapper
+    /// selectors always stand for one item.
+    pub fn count(&self) -> Amount {
+        match self {
+            Ingredient::Item { count, .. } => *count,
+            Ingredient::Tag(_) | Ingredient::Mapper(_) => Amount::Int(1),
+            Ingredient::Fluid { amount, .. } => Amount::Float(*amount),
+        }
+    }
+
+    /// Fold `other` into this ingredient if both name the same item,
+    /// returning whether it did. Only items are merged.
+    pub(crate) fn merge(&mut self, other: &Ingredient) -> bool {
+        match (self, other) {
+            (
+                Ingredient::Item { name, count },
+                Ingredient::Item {
+                    name: other_name,
+                    count: other_count,
+                },
+            ) if name == other_name => {
+                *count = *count + *other_count;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl From<&str> for Ingredient {
+    fn from(name: &str) -> Self {
+        Ingredient::new(name, 1)
+    }
+}
+
+impl From<String> for Ingredient {
+    fn from(name: String) -> Self {
+        Ingredient::new(name, 1)
+    }
+}
+
+impl fmt::Display for Ingredient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ingredient::Item {
+                name,
+                count: Amount::Int(1),
+            } => write!(f, "{}", escape_name(name)),
+            Ingredient::Item { name, count } => write!(f, "{}={}", escape_name(name), count),
+            Ingredient::Tag(tag) => write!(f, "[{}]", tag),
+            Ingredient::Mapper(mapper) => write!(f, "mapper:{}", mapper),
+            Ingredient::Fluid { name, amount } => {
+                write!(f, "fluid:{}={}", name, Amount::Float(*amount))
+            }
+        }
+    }
+}
+
+/// Escape the characters `escaped_identifier` would otherwise stop at.
+fn escape_name(name: &str) -> String {
+    name.replace('\\', "\\\\").replace(' ', "\\ ")
+}
+
+pub fn ingredient<'a, E>(input: &'a str) -> IResult<&'a str, Ingredient, E>
+where
+    E: ParseError<&'a str>,
+{
+    alt((
+        map(delimited(tag("["), identifier1, tag("]")), Ingredient::tag),
+        map(preceded(tag("mapper:"), identifier1), Ingredient::mapper),
+        map(
+            separated_pair(preceded(tag("fluid:"), identifier1), tag("="), float),
+            |(name, amount)| Ingredient::fluid(name, amount),
+        ),
+        map(
+            pair(continued_name, opt(preceded(tag("="), amount))),
+            |(name, count)| Ingredient::with_amount(name, count.unwrap_or_default()),
+        ),
+    ))(input)
+}
+
+/// The alternate form with the amount first, as in `2 Nails`.
+pub(crate) fn leading_count_ingredient<'a, E>(input: &'a str) -> IResult<&'a str, Ingredient, E>
+where
+    E: ParseError<&'a str>,
+{
+    map(
+        separated_pair(amount, space1, continued_name),
+        |(count, name)| Ingredient::with_amount(name, count),
+    )(input)
+}
+
+/// An ingredient name, which may be broken across lines by ending each
+/// line but the last with `|`. The pieces are joined without the breaks.
+fn continued_name<'a, E>(input: &'a str) -> IResult<&'a str, String, E>
+where
+    E: ParseError<&'a str>,
+{
+    let continuation = tuple((tag("|"), space0, line_ending, multispace0));
+    map(
+        pair(
+            escaped_identifier,
+            many0(preceded(continuation, escaped_identifier)),
+        ),
+        |(first, rest)| rest.into_iter().fold(first, |name, piece| name + &piece),
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Result<T> = IResult<&'static str, T, nom::error::Error<&'static str>>;
+
+    #[test]
+    fn parse_bare_ingredient() {
+        let ingredient_res: Result<Ingredient> = ingredient("Base.Milk,");
+        let (rest, actual) = ingredient_res.expect("failed to parse ingredient");
+
+        assert_eq!(",", rest);
+        assert_eq!(Ingredient::new("Base.Milk", 1), actual);
+    }
+
+    #[test]
+    fn parse_ingredient_with_count() {
+        let ingredient_res: Result<Ingredient> = ingredient("Nails=2,");
+        let (rest, actual) = ingredient_res.expect("failed to parse ingredient");
+
+        assert_eq!(",", rest);
+        assert_eq!(Ingredient::new("Nails", 2), actual);
+    }
+
+    #[test]
+    fn parse_tag_ingredient() {
+        let ingredient_res: Result<Ingredient> = ingredient("[Hammer],");
+        let (rest, actual) = ingredient_res.expect("failed to parse ingredient");
+
+        assert_eq!(",", rest);
+        assert_eq!(Ingredient::tag("Hammer"), actual);
+        assert_eq!("[Hammer]", actual.to_string());
+    }
+
+    #[test]
+    fn parse_ingredient_with_escaped_spaces() {
+        let ingredient_res: Result<Ingredient> = ingredient("Name\\ Thing,");
+        let (rest, actual) = ingredient_res.expect("failed to parse ingredient");
+
+        assert_eq!(",", rest);
+        assert_eq!(Ingredient::new("Name Thing", 1), actual);
+        assert_eq!("Name\\ Thing", actual.to_string());
+    }
+
+    #[test]
+    fn parse_fluid_ingredient() {
+        let ingredient_res: Result<Ingredient> = ingredient("fluid:Water=0.5,");
+        let (rest, actual) = ingredient_res.expect("failed to parse ingredient");
+
+        assert_eq!(",", rest);
+        assert_eq!(Ingredient::fluid("Water", 0.5), actual);
+        assert_eq!("fluid:Water=0.5", actual.to_string());
+    }
+
+    #[test]
+    fn parse_mapper_ingredient() {
+        let ingredient_res: Result<Ingredient> = ingredient("mapper:bucketEmpty,");
+        let (rest, actual) = ingredient_res.expect("failed to parse ingredient");
+
+        assert_eq!(",", rest);
+        assert_eq!(Ingredient::mapper("bucketEmpty"), actual);
+        assert_eq!("mapper:bucketEmpty", actual.to_string());
+    }
+
+    #[test]
+    fn parse_ingredient_continued_across_lines() {
+        let ingredient_res: Result<Ingredient> = ingredient("Base.GardeningSpray|\n    Empty=2,");
+        let (rest, actual) = ingredient_res.expect("failed to parse ingredient");
+
+        assert_eq!(",", rest);
+        assert_eq!(Ingredient::new("Base.GardeningSprayEmpty", 2), actual);
+    }
+
+    #[test]
+    fn parse_fractional_ingredient() {
+        let ingredient_res: Result<Ingredient> = ingredient("Water=0.5,");
+        let (rest, actual) = ingredient_res.expect("failed to parse ingredient");
+
+        assert_eq!(",", rest);
+        assert_eq!(Ingredient::with_amount("Water", Amount::Float(0.5)), actual);
+        assert_eq!("Water=0.5", actual.to_string());
+    }
+
+    #[test]
+    fn parse_whole_ingredient_count() {
+        let ingredient_res: Result<Ingredient> = ingredient("Nails=3,");
+        let (_, actual) = ingredient_res.expect("failed to parse ingredient");
+
+        assert_eq!(Amount::Int(3), actual.count());
+    }
+}
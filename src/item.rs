@@ -0,0 +1,641 @@
+use nom::{
+    branch::alt,
+    bytes::complete::{is_not, tag},
+    character::complete::{alphanumeric1, space0},
+    combinator::{cut, map, opt},
+    error::{ErrorKind, ParseError},
+    multi::separated_list0,
+    number::complete::recognize_float,
+    sequence::{delimited, pair, preceded},
+    IResult, Parser,
+};
+
+use crate::{
+    field_value_separated_by, leading_comments, line_end, list_value, named_block_with_options,
+    normalize_name, quoted_string_with, separator, text_value, trailing_comments, Named,
+    ParseOptions, WeaponStats,
+};
+
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Item {
+    name: String,
+    item_type: Option<String>,
+    display_name: Option<String>,
+    display_category: Option<String>,
+    icon: Option<String>,
+    icons_for_texture: Vec<String>,
+    capacity: Option<u64>,
+    weapon: Option<WeaponStats>,
+    extra: Vec<(String, String)>,
+    leading_comments: Vec<String>,
+}
+
+#[derive(Default)]
+struct ItemBody<'a> {
+    item_type: Option<&'a str>,
+    display_name: Option<&'a str>,
+    display_category: Option<&'a str>,
+    icon: Option<&'a str>,
+    icons_for_texture: Vec<&'a str>,
+    capacity: Option<u64>,
+    weapon: Option<WeaponStats>,
+    extra: Vec<(&'a str, &'a str)>,
+}
+
+enum ItemField<'a> {
+    Type(&'a str),
+    DisplayName(&'a str),
+    DisplayCategory(&'a str),
+    Icon(&'a str),
+    IconsForTexture(Vec<&'a str>),
+    Capacity(u64),
+    Unknown(&'a str, &'a str),
+}
+
+impl Item {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            item_type: None,
+            display_name: None,
+            display_category: None,
+            icon: None,
+            icons_for_texture: Vec::new(),
+            capacity: None,
+            weapon: None,
+            extra: Vec::new(),
+            leading_comments: Vec::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn item_type(&self) -> Option<&str> {
+        self.item_type.as_deref()
+    }
+
+    pub fn display_name(&self) -> Option<&str> {
+        self.display_name.as_deref()
+    }
+
+    pub fn display_category(&self) -> Option<&str> {
+        self.display_category.as_deref()
+    }
+
+    pub fn icon(&self) -> Option<&str> {
+        self.icon.as_deref()
+    }
+
+    /// Icon variants picked by texture, from `IconsForTexture = a;b;c`.
+    pub fn icons_for_texture(&self) -> &[String] {
+        &self.icons_for_texture
+    }
+
+    pub fn capacity(&self) -> Option<u64> {
+        self.capacity
+    }
+
+    /// Combat stats, present only for items with `Type = Weapon`.
+    pub fn weapon(&self) -> Option<&WeaponStats> {
+        self.weapon.as_ref()
+    }
+
+    /// Fields without a typed accessor, in source order. Items have far
+    /// more fields than recipes, so these are always collected.
+    pub fn extra(&self) -> &[(String, String)] {
+        &self.extra
+    }
+
+    pub(crate) fn extra_mut(&mut self) -> &mut Vec<(String, String)> {
+        &mut self.extra
+    }
+
+    /// Comments directly above the item. Only populated when parsing with
+    /// `attach_comments`.
+    pub fn leading_comments(&self) -> &[String] {
+        &self.leading_comments
+    }
+
+    pub(crate) fn set_leading_comments(&mut self, comments: Vec<String>) {
+        self.leading_comments = comments;
+    }
+
+    /// Only quoted values can span lines, and those end up in the display
+    /// name or among the extra fields.
+    fn normalize_line_endings(&mut self) {
+        if let Some(display_name) = &mut self.display_name {
+            *display_name = display_name.replace("\r\n", "\n");
+        }
+        for (_, value) in &mut self.extra {
+            *value = value.replace("\r\n", "\n");
+        }
+    }
+}
+
+impl Named for Item {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<'a> From<(&'a str, ItemBody<'a>)> for Item {
+    fn from((name, body): (&'a str, ItemBody<'a>)) -> Self {
+        let ItemBody {
+            item_type,
+            display_name,
+            display_category,
+            icon,
+            icons_for_texture,
+            capacity,
+            weapon,
+            extra,
+        } = body;
+        Item {
+            name: normalize_name(name),
+            item_type: item_type.map(str::to_string),
+            display_name: display_name.map(str::to_string),
+            display_category: display_category.map(str::to_string),
+            icon: icon.map(str::to_string),
+            icons_for_texture: icons_for_texture.into_iter().map(str::to_string).collect(),
+            capacity,
+            weapon,
+            extra: extra
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            leading_comments: Vec::new(),
+        }
+    }
+}
+
+/// A whole number that must fit in a `u64`. Scientific notation such as
+/// `1e6` is accepted as long as it denotes a whole number; anything out of
+/// range is a `TooLarge` error rather than being truncated, and negative or
+/// fractional numbers are a `Digit` error.
+fn count_value<'a, E>(input: &'a str) -> IResult<&'a str, u64, E>
+where
+    E: ParseError<&'a str>,
+{
+    let (rest, number) = recognize_float(input)?;
+    let error = |kind| Err(nom::Err::Error(E::from_error_kind(input, kind)));
+
+    // Without an exponent the digits are read directly, so the whole `u64`
+    // range is exact; a fraction of only zeros still denotes a whole number.
+    if !number.contains(['e', 'E']) {
+        let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+        let whole = whole.strip_prefix('+').unwrap_or(whole);
+        if !fraction.bytes().all(|b| b == b'0') {
+            return error(ErrorKind::Digit);
+        }
+        if whole.starts_with('-') {
+            return error(ErrorKind::Digit);
+        }
+        if whole.is_empty() {
+            return Ok((rest, 0));
+        }
+        return match whole.parse::<u64>() {
+            Ok(count) => Ok((rest, count)),
+            Err(_) => error(ErrorKind::TooLarge),
+        };
+    }
+
+    match number.parse::<f64>() {
+        Ok(count) if count < 0.0 || count.fract() != 0.0 => error(ErrorKind::Digit),
+        // 2^64 is exactly representable, unlike `u64::MAX`.
+        Ok(count) if count < 2f64.powi(64) => Ok((rest, count as u64)),
+        _ => error(ErrorKind::TooLarge),
+    }
+}
+
+/// One entry of a `;`-separated list, without surrounding whitespace.
+fn list_item<'a, E>(input: &'a str) -> IResult<&'a str, &'a str, E>
+where
+    E: ParseError<&'a str>,
+{
+    map(is_not(";,\r\n"), str::trim)(input)
+}
+
+fn unknown_field<'a, E>(
+    options: ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, ItemField<'a>, E>
+where
+    E: ParseError<&'a str>,
+{
+    move |input: &'a str| {
+        let (input, name) = preceded(space0, alphanumeric1)(input)?;
+        let (input, _) = delimited(space0, field_separator, space0)(input)?;
+        let (input, value) = map(
+            opt(alt((quoted_string_with(options), text_value))),
+            Option::unwrap_or_default,
+        )(input)?;
+        let (input, _) = pair(opt(tag(",")), line_end)(input)?;
+        Ok((input, ItemField::Unknown(name, value)))
+    }
+}
+
+/// Item fields are normally written `Name = value`, but a `Name:value`
+/// line turns up now and then in the middle of an otherwise normal body.
+fn field_separator<'a, E>(input: &'a str) -> IResult<&'a str, &'a str, E>
+where
+    E: ParseError<&'a str>,
+{
+    alt((tag("="), tag(":")))(input)
+}
+
+/// A field that ends at the end of its line, with either separator.
+fn item_value<'a, F, O, E>(
+    field_name: &'static str,
+    value: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O, E>
+where
+    F: Parser<&'a str, O, E>,
+    E: ParseError<&'a str>,
+{
+    field_value_separated_by(
+        field_name,
+        field_separator,
+        value,
+        pair(opt(tag(",")), line_end),
+    )
+}
+
+fn item_field<'a, E>(
+    options: ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, ItemField<'a>, E>
+where
+    E: ParseError<&'a str>,
+{
+    move |input: &'a str| {
+        alt((
+            map(item_value("Type", text_value), ItemField::Type),
+            map(
+                item_value(
+                    "DisplayName",
+                    alt((quoted_string_with(options), text_value)),
+                ),
+                ItemField::DisplayName,
+            ),
+            map(
+                item_value("DisplayCategory", text_value),
+                ItemField::DisplayCategory,
+            ),
+            map(item_value("Icon", text_value), ItemField::Icon),
+            map(
+                item_value("IconsForTexture", list_value(list_item)),
+                ItemField::IconsForTexture,
+            ),
+            map(
+                item_value("Capacity", cut(count_value)),
+                ItemField::Capacity,
+            ),
+            unknown_field(options),
+        ))(input)
+    }
+}
+
+fn item_body<'a, E>(
+    options: ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, ItemBody<'a>, E>
+where
+    E: ParseError<&'a str>,
+{
+    move |input: &'a str| {
+        let (input, fields) = delimited(
+            leading_comments(options),
+            separated_list0(separator(options), item_field(options)),
+            trailing_comments(options),
+        )(input)?;
+
+        let mut body = ItemBody::default();
+        for field in fields {
+            match field {
+                ItemField::Type(value) => body.item_type = Some(value),
+                ItemField::DisplayName(value) => body.display_name = Some(value),
+                ItemField::DisplayCategory(value) => body.display_category = Some(value),
+                ItemField::Icon(value) => body.icon = Some(value),
+                ItemField::IconsForTexture(value) => body.icons_for_texture = value,
+                ItemField::Capacity(value) => body.capacity = Some(value),
+                ItemField::Unknown(name, value) => body.extra.push((name, value)),
+            }
+        }
+        if body.item_type == Some("Weapon") {
+            body.weapon = Some(WeaponStats::extract(&mut body.extra)?);
+        }
+        Ok((input, body))
+    }
+}
+
+pub fn item<'a, E>(input: &'a str) -> IResult<&'a str, Item, E>
+where
+    E: ParseError<&'a str>,
+{
+    item_with_options(ParseOptions::default())(input)
+}
+
+pub fn item_with_options<'a, E>(
+    options: ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Item, E>
+where
+    E: ParseError<&'a str>,
+{
+    move |input: &'a str| {
+        let (input, mut item): (_, Item) = Parser::into(named_block_with_options(
+            "item",
+            options,
+            item_body(options),
+        ))
+        .parse(input)?;
+        if options.normalize_line_endings {
+            item.normalize_line_endings();
+        }
+        Ok((input, item))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Result<T> = IResult<&'static str, T, nom::error::Error<&'static str>>;
+
+    #[test]
+    fn parse_comma_free_item() {
+        let item_text = "item RedRadish
+{
+  DisplayCategory = Food
+  Type            = Food
+  DisplayName     = Red Radish
+  Icon            = Radish
+  Weight          = 0.1
+}";
+
+        let item_res: Result<Item> = item(item_text);
+        let (_, actual) = item_res.expect("failed to parse item");
+
+        assert_eq!("RedRadish", actual.name());
+        assert_eq!(Some("Food"), actual.item_type());
+        assert_eq!(Some("Red Radish"), actual.display_name());
+        assert_eq!(Some("Food"), actual.display_category());
+        assert_eq!(Some("Radish"), actual.icon());
+        assert_eq!(
+            &[(String::from("Weight"), String::from("0.1"))],
+            actual.extra()
+        );
+    }
+
+    #[test]
+    fn parse_mixed_field_separators() {
+        let item_text = "item RedRadish
+{
+    Type = Food,
+    DisplayName:Red Radish,
+    Weight:0.1,
+    Icon = Radish,
+}";
+
+        let item_res: Result<Item> = item(item_text);
+        let (_, actual) = item_res.expect("failed to parse item");
+
+        assert_eq!(Some("Food"), actual.item_type());
+        assert_eq!(Some("Red Radish"), actual.display_name());
+        assert_eq!(Some("Radish"), actual.icon());
+        assert_eq!(
+            &[(String::from("Weight"), String::from("0.1"))],
+            actual.extra()
+        );
+    }
+
+    #[test]
+    fn parse_module_qualified_icon() {
+        let item_text = "item RedRadish
+{
+    Type = Food,
+    Icon = Base.Radish,
+}";
+
+        let item_res: Result<Item> = item(item_text);
+        let (_, actual) = item_res.expect("failed to parse item");
+
+        assert_eq!(Some("Base.Radish"), actual.icon());
+    }
+
+    #[test]
+    fn parse_item_with_commas() {
+        let item_text = "item RedRadish
+{
+  DisplayCategory = Food,
+  Type            = Food,
+  DisplayName     = Radish,
+  Icon            = Radish,
+}";
+
+        let item_res: Result<Item> = item(item_text);
+        let (_, actual) = item_res.expect("failed to parse item");
+
+        assert_eq!(Some("Food"), actual.item_type());
+        assert_eq!(Some("Radish"), actual.display_name());
+        assert!(actual.extra().is_empty());
+    }
+
+    #[test]
+    fn parse_weapon_stats() {
+        let item_text = "item Axe
+{
+    DisplayCategory = Weapon,
+    Type = Weapon,
+    DisplayName = Axe,
+    MinDamage = 0.8,
+    MaxDamage = 2,
+    SwingTime = 3.5,
+    MaxHitCount = 2,
+    Weight = 3,
+}";
+
+        let item_res: Result<Item> = item(item_text);
+        let (_, actual) = item_res.expect("failed to parse item");
+        let weapon = actual.weapon().expect("expected weapon stats");
+
+        assert_eq!(Some(0.8), weapon.min_damage);
+        assert_eq!(Some(2.0), weapon.max_damage);
+        assert_eq!(Some(3.5), weapon.swing_time);
+        assert_eq!(Some(2), weapon.max_hit_count);
+        assert_eq!(None, weapon.critical_chance);
+        assert_eq!(
+            &[(String::from("Weight"), String::from("3"))],
+            actual.extra()
+        );
+    }
+
+    #[test]
+    fn non_weapon_items_keep_weapon_fields_in_extra() {
+        let item_text = "item Radish
+{
+    Type = Food,
+    MinDamage = 0.1,
+}";
+
+        let item_res: Result<Item> = item(item_text);
+        let (_, actual) = item_res.expect("failed to parse item");
+
+        assert_eq!(None, actual.weapon());
+        assert_eq!(
+            &[(String::from("MinDamage"), String::from("0.1"))],
+            actual.extra()
+        );
+    }
+
+    #[test]
+    fn non_numeric_weapon_stat_is_an_error() {
+        let item_text = "item Axe
+{
+    Type = Weapon,
+    MinDamage = lots,
+}";
+
+        let item_res: Result<Item> = item(item_text);
+
+        assert!(item_res.is_err());
+    }
+
+    #[test]
+    fn parse_large_capacity() {
+        let item_text = "item MegaCrate
+{
+    Type = Container,
+    Capacity = 18446744073709551614,
+}";
+
+        let item_res: Result<Item> = item(item_text);
+        let (_, actual) = item_res.expect("failed to parse item");
+
+        assert_eq!(Some(u64::MAX - 1), actual.capacity());
+    }
+
+    #[test]
+    fn parse_scientific_capacity() {
+        let item_text = "item MegaCrate
+{
+    Capacity = 1e6,
+}";
+
+        let item_res: Result<Item> = item(item_text);
+        let (_, actual) = item_res.expect("failed to parse item");
+
+        assert_eq!(Some(1_000_000), actual.capacity());
+    }
+
+    #[test]
+    fn overflowing_capacity_is_an_error() {
+        let item_text = "item MegaCrate
+{
+    Capacity = 18446744073709551616,
+}";
+
+        let item_res: Result<Item> = item(item_text);
+
+        assert!(matches!(item_res, Err(nom::Err::Failure(_))));
+    }
+
+    #[test]
+    fn count_value_error_kinds() {
+        let count_res: Result<u64> = count_value("18446744073709551615.0,");
+        assert_eq!(Ok((",", u64::MAX)), count_res);
+
+        for (count_text, kind) in [
+            ("1.5,", ErrorKind::Digit),
+            ("-1,", ErrorKind::Digit),
+            ("1.5e0,", ErrorKind::Digit),
+            ("18446744073709551616,", ErrorKind::TooLarge),
+            ("1e20,", ErrorKind::TooLarge),
+        ] {
+            let count_res: Result<u64> = count_value(count_text);
+            let Err(nom::Err::Error(err)) = count_res else {
+                panic!("parsed {:?}", count_text);
+            };
+            assert_eq!(kind, err.code, "wrong error for {:?}", count_text);
+        }
+    }
+
+    #[test]
+    fn parse_icons_for_texture() {
+        let item_text = "item Shirt
+{
+    Type = Clothing,
+    IconsForTexture = ShirtRed;ShirtGreen;ShirtBlue,
+}";
+
+        let item_res: Result<Item> = item(item_text);
+        let (_, actual) = item_res.expect("failed to parse item");
+
+        assert_eq!(
+            &[
+                String::from("ShirtRed"),
+                String::from("ShirtGreen"),
+                String::from("ShirtBlue")
+            ],
+            actual.icons_for_texture()
+        );
+        assert!(actual.extra().is_empty());
+    }
+
+    #[test]
+    fn parse_quoted_unicode_display_name() {
+        let item_text = "item Coffee
+{
+    Type = Food,
+    DisplayName = \"Café, hot ☕\",
+}";
+
+        let item_res: Result<Item> = item(item_text);
+        let (_, actual) = item_res.expect("failed to parse item");
+
+        assert_eq!(Some("Café, hot ☕"), actual.display_name());
+    }
+
+    #[test]
+    fn multiline_quoted_value_requires_option() {
+        let item_text = "item Cake
+{
+    Type = Food,
+    Tooltip = \"Sweet,
+and fluffy\",
+}";
+
+        let strict_res: Result<Item> = item(item_text);
+        assert!(strict_res.is_err());
+
+        let options = ParseOptions {
+            multiline_strings: true,
+            ..ParseOptions::default()
+        };
+        let item_res: Result<Item> = item_with_options(options)(item_text);
+        let (_, actual) = item_res.expect("failed to parse item");
+
+        assert_eq!(
+            &[(String::from("Tooltip"), String::from("Sweet,\nand fluffy"))],
+            actual.extra()
+        );
+    }
+
+    #[test]
+    fn crlf_multiline_value_normalizes_to_lf() {
+        let lf_text = "item Cake\n{\n    Tooltip = \"Sweet,\nand fluffy\",\n}";
+        let crlf_text = lf_text.replace('\n', "\r\n");
+
+        let options = ParseOptions {
+            multiline_strings: true,
+            normalize_line_endings: true,
+            ..ParseOptions::default()
+        };
+        let lf_res: Result<Item> = item_with_options(options)(lf_text);
+        let (_, lf_item) = lf_res.expect("failed to parse LF item");
+        let crlf_res: IResult<&str, Item, nom::error::Error<&str>> =
+            item_with_options(options)(&crlf_text);
+        let (_, crlf_item) = crlf_res.expect("failed to parse CRLF item");
+
+        assert_eq!(lf_item, crlf_item);
+    }
+}
@@ -1,39 +1,301 @@
 use nom::{
     branch::alt,
-    bytes::complete::tag,
-    character::complete::space0,
-    combinator::map,
+    bytes::complete::{escaped_transform, is_not, tag, tag_no_case},
+    character::complete::{line_ending, space0},
+    combinator::{eof, map, opt, peek, recognize, value, verify},
     error::ParseError,
-    sequence::{delimited, preceded},
+    multi::separated_list1,
+    number::complete::float,
+    sequence::{delimited, pair, preceded},
     AsChar, IResult, InputTakeAtPosition, Parser,
 };
 
+/// Trace-level logging of parser progress. Without the `logging` feature
+/// this expands to nothing, so the arguments are never evaluated.
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "logging")]
+        log::trace!($($arg)*);
+    };
+}
+
+mod amount;
+pub use amount::{amount, Amount};
+
+mod anim_node;
+pub use anim_node::AnimNode;
+
 mod block;
-pub use block::{named_block, named_block_repeated, unnamed_block};
+use block::named_block_with_options;
+pub use block::{
+    block_name, named_block, named_block_repeated, named_block_repeated_spaced, unnamed_block,
+    unnamed_block_repeated, Spacing,
+};
+
+mod ingredient;
+pub use ingredient::{ingredient, Ingredient};
+
+mod category;
+pub use category::RecipeCategory;
+
+mod comment;
+pub use comment::comment;
+use comment::{leading_comments, separator, trailing_comments, whitespace0};
+
+mod definition;
+pub use definition::{definition, Definition, Named};
+
+mod document;
+pub use document::{parse_document, Document, Symbol, SymbolKind};
+
+mod file;
+pub use file::{
+    parse_dir, parse_document_flat, parse_file, parse_file_with_options, parse_file_with_stats,
+    parse_one_module, parse_reader, FileResult, ParseStats, ReadError,
+};
+
+mod fixer;
+pub use fixer::{fixer, Fixer};
+
+mod item;
+pub use item::{item, item_with_options, Item};
+
+mod lint;
+pub use lint::{lint, Lint, LintKind};
+
+mod model;
+pub use model::{model, Model};
 
 mod module;
-pub use module::{module, Module, ModuleBlock};
+pub use module::{
+    merge_modules, module, BlockDiff, MergePolicy, Module, ModuleBlock, ModuleBlockBuilder,
+};
+
+mod need;
+pub use need::{need, Need};
+
+mod option;
+pub use option::{option, option_with_options, Option_};
+
+mod options;
+pub use options::ParseOptions;
+
+mod parsable;
+pub use parsable::{parse_all, ParsableBlock};
 
 mod recipe;
-pub use recipe::{recipe, Recipe};
+pub use recipe::{
+    default_ingredient, recipe, recipe_partial, recipe_v2, recipe_with_ingredient_parser,
+    recipe_with_options, Recipe, RecipeByName, RecipeError, RecipeParser,
+};
+
+mod result_spec;
+pub use result_spec::{result_spec, ResultSpec};
 
+mod skill;
+pub use skill::{skill_requirement, SkillReq};
+
+mod weapon;
+pub use weapon::WeaponStats;
+
+pub mod verbose;
+
+/// Block names keep their internal whitespace, but tabs are normalized to
+/// spaces so that tab-indented files produce the same names as
+/// space-indented ones.
+fn normalize_name(name: &str) -> String {
+    name.replace('\t', " ")
+}
+
+#[cfg(test)]
 fn field_value<'a, 'b, 'c, F, O, E>(
     field_name: &'b str,
     separator: &'c str,
-    mut value: F,
+    value: F,
 ) -> impl FnMut(&'a str) -> IResult<&'a str, O, E>
 where
     'b: 'a,
     'c: 'a,
     F: Parser<&'a str, O, E>,
     E: ParseError<&'a str>,
+{
+    field_value_terminated(field_name, separator, value, tag(","))
+}
+
+fn field_value_terminated<'a, 'b, 'c, F, T, O, P, E>(
+    field_name: &'b str,
+    separator: &'c str,
+    value: F,
+    terminator: T,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O, E>
+where
+    'b: 'a,
+    'c: 'a,
+    F: Parser<&'a str, O, E>,
+    T: Parser<&'a str, P, E>,
+    E: ParseError<&'a str>,
+{
+    field_value_separated_by(field_name, tag(separator), value, terminator)
+}
+
+/// Like `field_value_terminated`, with the separator given as a parser so
+/// that a field may accept more than one.
+fn field_value_separated_by<'a, 'b, S, F, T, P, O, Q, E>(
+    field_name: &'b str,
+    mut separator: S,
+    mut value: F,
+    mut terminator: T,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O, E>
+where
+    'b: 'a,
+    S: Parser<&'a str, Q, E>,
+    F: Parser<&'a str, O, E>,
+    T: Parser<&'a str, P, E>,
+    E: ParseError<&'a str>,
 {
     move |input: &'a str| {
         let (input, _) = preceded(space0, tag(field_name))(input)?;
+        let (input, _) = delimited(space0, |i| separator.parse(i), space0)(input)?;
+        let (input, parsed_value) = value.parse(input)?;
+        let (input, _) = terminator.parse(input)?;
+        Ok((input, parsed_value))
+    }
+}
+
+/// A `name = value,` field whose name is matched by a parser rather than
+/// given up front, returning the name alongside the value. Useful for
+/// collecting the fields of a body generically.
+pub fn field_value_named<'a, 'c, N, F, O, E>(
+    mut name: N,
+    separator: &'c str,
+    mut value: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (&'a str, O), E>
+where
+    'c: 'a,
+    N: Parser<&'a str, &'a str, E>,
+    F: Parser<&'a str, O, E>,
+    E: ParseError<&'a str>,
+{
+    move |input: &'a str| {
+        let (input, field_name) = preceded(space0, |i| name.parse(i))(input)?;
         let (input, _) = delimited(space0, tag(separator), space0)(input)?;
         let (input, parsed_value) = value.parse(input)?;
         let (input, _) = tag(",")(input)?;
-        Ok((input, parsed_value))
+        Ok((input, (field_name, parsed_value)))
+    }
+}
+
+/// Like `field_value`, but the field ends at the end of its line rather
+/// than at a comma. A comma before the line break is tolerated.
+fn field_value_nl<'a, 'b, 'c, F, O, E>(
+    field_name: &'b str,
+    separator: &'c str,
+    value: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O, E>
+where
+    'b: 'a,
+    'c: 'a,
+    F: Parser<&'a str, O, E>,
+    E: ParseError<&'a str>,
+{
+    field_value_terminated(field_name, separator, value, pair(opt(tag(",")), line_end))
+}
+
+/// Succeeds without consuming anything when only whitespace remains before
+/// the end of the line, the closing brace of a block or the end of input.
+fn line_end<'a, E>(input: &'a str) -> IResult<&'a str, &'a str, E>
+where
+    E: ParseError<&'a str>,
+{
+    recognize(peek(pair(space0, alt((line_ending, tag("}"), eof)))))(input)
+}
+
+/// The comma that ends a field. When the options allow it the comma may be
+/// left off, as long as nothing but the end of the line or block follows.
+fn field_terminator<'a, E>(
+    options: ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str, E>
+where
+    E: ParseError<&'a str>,
+{
+    move |input: &'a str| {
+        if options.allow_trailing_comma {
+            alt((terminator_char(options), line_end))(input)
+        } else {
+            terminator_char(options)(input)
+        }
+    }
+}
+
+fn terminator_char<'a, E>(
+    options: ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str, E>
+where
+    E: ParseError<&'a str>,
+{
+    move |input: &'a str| {
+        if options.semicolon_terminators {
+            alt((tag(","), tag(";")))(input)
+        } else {
+            tag(",")(input)
+        }
+    }
+}
+
+fn list_value<'a, F, O, E>(item: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>, E>
+where
+    F: Parser<&'a str, O, E>,
+    E: ParseError<&'a str>,
+{
+    separated_list1(tag(";"), item)
+}
+
+/// Free text running up to the field terminator, such as a multi-word
+/// `Category`. Trailing whitespace before the comma is not included.
+fn text_value<'a, E>(input: &'a str) -> IResult<&'a str, &'a str, E>
+where
+    E: ParseError<&'a str>,
+{
+    map(is_not(",\r\n"), str::trim_end)(input)
+}
+
+/// Like `text_value`, but also stopping at a `;` when the options allow it
+/// to end a field.
+fn text_value_with<'a, E>(
+    options: ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str, E>
+where
+    E: ParseError<&'a str>,
+{
+    move |input: &'a str| {
+        if options.semicolon_terminators {
+            map(is_not(",;\r\n"), str::trim_end)(input)
+        } else {
+            text_value(input)
+        }
+    }
+}
+
+/// Text between double quotes, which may contain commas and any Unicode.
+/// The quotes are not included, and may span several lines when the
+/// options allow it.
+fn quoted_string_with<'a, E>(
+    options: ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str, E>
+where
+    E: ParseError<&'a str>,
+{
+    move |input: &'a str| {
+        let forbidden = if options.multiline_strings {
+            "\""
+        } else {
+            "\"\r\n"
+        };
+        delimited(
+            tag("\""),
+            map(opt(is_not(forbidden)), Option::unwrap_or_default),
+            tag("\""),
+        )(input)
     }
 }
 
@@ -44,19 +306,92 @@ where
     alt((map(tag("true"), |_| true), map(tag("false"), |_| false)))(input)
 }
 
+fn bool_value_with<'a, E>(options: ParseOptions) -> impl FnMut(&'a str) -> IResult<&'a str, bool, E>
+where
+    E: ParseError<&'a str>,
+{
+    move |input: &'a str| {
+        if options.lenient_bools {
+            alt((
+                map(alt((tag_no_case("true"), tag("1"))), |_| true),
+                map(alt((tag_no_case("false"), tag("0"))), |_| false),
+            ))(input)
+        } else {
+            bool_value(input)
+        }
+    }
+}
+
+/// A duration in seconds. Bare numbers are seconds already; an `h`, `m` or
+/// `s` suffix gives the unit explicitly. `inf` and `NaN` are refused, as
+/// `Recipe::set_time` would refuse them.
+fn time_value<'a, E>(input: &'a str) -> IResult<&'a str, f32, E>
+where
+    E: ParseError<&'a str>,
+{
+    verify(
+        map(
+            pair(
+                float,
+                opt(alt((
+                    map(tag("h"), |_| 3600.0),
+                    map(tag("m"), |_| 60.0),
+                    map(tag("s"), |_| 1.0),
+                ))),
+            ),
+            |(time, unit)| time * unit.unwrap_or(1.0),
+        ),
+        |time: &f32| time.is_finite(),
+    )(input)
+}
+
+/// A parser for a non-empty run of alphanumerics and any of the characters
+/// in `extra_chars`, for fields that allow some punctuation in names.
+///
+/// ```
+/// use pz_data::identifier_with;
+///
+/// let (rest, icon) =
+///     identifier_with::<nom::error::Error<&str>>("._/")("Base.Food/Radish_1,").unwrap();
+/// assert_eq!("Base.Food/Radish_1", icon);
+/// assert_eq!(",", rest);
+/// ```
+pub fn identifier_with<'a, 'b, E>(
+    extra_chars: &'b str,
+) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str, E> + use<'a, 'b, E>
+where
+    E: ParseError<&'a str>,
+{
+    move |input: &'a str| {
+        input.split_at_position1_complete(
+            |item| !(item.is_alphanum() || extra_chars.contains(item)),
+            nom::error::ErrorKind::RegexpFind,
+        )
+    }
+}
+
+/// An identifier that may be module-qualified, as in `Base.Milk`.
 fn identifier1<'a, E>(input: &'a str) -> IResult<&'a str, &'a str, E>
 where
     E: ParseError<&'a str>,
 {
-    input.split_at_position1_complete(
-        |item| {
-            if item.is_alphanum() {
-                return false;
-            }
-            item.as_char() != '.'
-        },
-        nom::error::ErrorKind::RegexpFind,
-    )
+    identifier_with(".")(input)
+}
+
+/// Like `identifier1`, but a space or backslash may be escaped with a
+/// backslash, so that `Name\ With\ Spaces` is read as `Name With Spaces`.
+fn escaped_identifier<'a, E>(input: &'a str) -> IResult<&'a str, String, E>
+where
+    E: ParseError<&'a str>,
+{
+    verify(
+        escaped_transform(
+            identifier1,
+            '\\',
+            alt((value(" ", tag(" ")), value("\\", tag("\\")))),
+        ),
+        |identifier: &str| !identifier.is_empty(),
+    )(input)
 }
 
 #[cfg(test)]
@@ -96,6 +431,82 @@ mod tests {
         ))
     }
 
+    #[test]
+    fn collect_named_fields() {
+        let test_text = "item RedRadish {
+  DisplayCategory = Food,
+  Type            = Food,
+  DisplayName     = Radish,
+}";
+        let expected = (
+            "RedRadish",
+            vec![
+                ("DisplayCategory", "Food"),
+                ("Type", "Food"),
+                ("DisplayName", "Radish"),
+            ],
+        );
+
+        let block_res: Result<(&str, Vec<(&str, &str)>)> = named_block(
+            "item",
+            separated_list1(
+                multispace1,
+                field_value_named(alphanumeric1, "=", alphanumeric1),
+            ),
+        )(test_text);
+        let (_, actual) = block_res.expect("failed to parse block");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn parse_escaped_spaces_in_identifier() {
+        let identifier_res: IResult<&str, String, nom::error::Error<&str>> =
+            escaped_identifier("Name\\ Thing,");
+        assert_eq!(Ok((",", String::from("Name Thing"))), identifier_res);
+    }
+
+    #[test]
+    fn identifier_with_extra_punctuation() {
+        let identifier_res: Result<&str> = identifier_with("._/")("Base.Food/Radish_1,");
+        assert_eq!(Ok((",", "Base.Food/Radish_1")), identifier_res);
+
+        let identifier_res: Result<&str> = identifier_with("._/")("/icons,");
+        assert_eq!(Ok((",", "/icons")), identifier_res);
+
+        let identifier_res: Result<&str> = identifier_with(".")("Food/Radish,");
+        assert_eq!(Ok(("/Radish,", "Food")), identifier_res);
+
+        let identifier_res: Result<&str> = identifier_with("._/")("-Radish");
+        assert!(identifier_res.is_err());
+    }
+
+    #[test]
+    fn parse_time_in_hours() {
+        let time_res: Result<f32> = time_value("2h,");
+        assert_eq!(Ok((",", 7200.0)), time_res);
+    }
+
+    #[test]
+    fn parse_time_in_minutes() {
+        let time_res: Result<f32> = time_value("30m,");
+        assert_eq!(Ok((",", 1800.0)), time_res);
+    }
+
+    #[test]
+    fn parse_bare_time_as_seconds() {
+        let time_res: Result<f32> = time_value("40.0,");
+        assert_eq!(Ok((",", 40.0)), time_res);
+    }
+
+    #[test]
+    fn reject_non_finite_time() {
+        for time_text in ["inf,", "infinity,", "NaN,", "1e38h,"] {
+            let time_res: Result<f32> = time_value(time_text);
+            assert!(time_res.is_err(), "parsed {:?}", time_text);
+        }
+    }
+
     #[test]
     fn parse_complex_item_heterogenous_block() {
         let test_text = "item RedRadish {
@@ -0,0 +1,237 @@
+use nom::{branch::alt, bytes::complete::tag, sequence::terminated};
+
+use crate::{
+    default_ingredient, line_end, parse_document,
+    recipe::{is_valid_time, FIELDS},
+    recipe_with_options, ParseOptions, SymbolKind,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LintKind {
+    /// A recipe field the parser doesn't know about.
+    UnknownField(String),
+    /// A field given more than once in the same definition. Only the last
+    /// value is kept.
+    DuplicateField(String),
+    /// A recipe whose `Time` is negative or not a number.
+    InvalidTime,
+    /// A definition that the parser rejects even with every option relaxed.
+    InvalidDefinition,
+    /// A block that is never closed. Nothing after it is checked.
+    UnterminatedBlock,
+}
+
+/// A problem found by `lint`, at a byte offset into the checked text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Lint {
+    pub kind: LintKind,
+    pub offset: usize,
+}
+
+impl Lint {
+    pub fn new(kind: LintKind, offset: usize) -> Self {
+        Self { kind, offset }
+    }
+}
+
+/// Check a whole file without stopping at the first problem. The file is
+/// outlined with `parse_document`, and each recipe is then parsed on its
+/// own with every relaxing option enabled, so one bad definition doesn't
+/// hide problems in the others. Lints are returned in file order.
+pub fn lint(input: &str) -> Vec<Lint> {
+    let options = ParseOptions {
+        allow_trailing_comma: true,
+        collect_unknown_fields: true,
+        lenient_bools: true,
+        allow_comments: true,
+        ..ParseOptions::default()
+    };
+
+    let document = parse_document(input);
+    let mut lints = Vec::new();
+    for symbol in &document.symbols {
+        let start = symbol.full_range.start;
+        let text = &input[symbol.full_range.clone()];
+        match symbol.kind {
+            SymbolKind::Recipe => {
+                lint_fields(text, start, &[':'], Some(FIELDS), &mut lints);
+                match recipe_with_options::<nom::error::Error<&str>>(options)(text) {
+                    Ok((_, parsed)) => {
+                        if !is_valid_time(parsed.time()) {
+                            lints.push(Lint::new(LintKind::InvalidTime, start));
+                        }
+                    }
+                    Err(_) => lints.push(Lint::new(LintKind::InvalidDefinition, start)),
+                }
+            }
+            SymbolKind::Item => lint_fields(text, start, &['=', ':'], None, &mut lints),
+            _ => {}
+        }
+    }
+    if let Some(offset) = document.unparsed {
+        lints.push(Lint::new(LintKind::UnterminatedBlock, offset));
+    }
+    lints.sort_by_key(|lint| lint.offset);
+    lints
+}
+
+/// Whether `line` is a whole recipe ingredient, such as `mapper:bucketEmpty,`
+/// or `fluid:Water=0.5,`, which would otherwise look like a field.
+fn is_ingredient_line(line: &str) -> bool {
+    terminated(
+        default_ingredient::<nom::error::Error<&str>>,
+        alt((tag(","), line_end)),
+    )(line)
+    .is_ok()
+}
+
+/// Look for `Name<separator>` at the start of each line of a block body,
+/// reporting duplicates and, when `known` is given, unrecognized names.
+/// Recipe bodies are checked against `known`, so their ingredient lines
+/// are skipped.
+fn lint_fields(
+    text: &str,
+    start: usize,
+    separators: &[char],
+    known: Option<&[&str]>,
+    lints: &mut Vec<Lint>,
+) {
+    let Some(body_start) = text.find('{') else {
+        return;
+    };
+    let mut seen: Vec<&str> = Vec::new();
+    let mut line_start = body_start + 1;
+    for line in text[body_start + 1..].split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let offset = start + line_start + (line.len() - trimmed.len());
+        line_start += line.len();
+
+        let name_len = trimmed
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(trimmed.len());
+        let (name, rest) = trimmed.split_at(name_len);
+        if name.is_empty() || !rest.trim_start().starts_with(separators) {
+            continue;
+        }
+        if known.is_some() && is_ingredient_line(trimmed) {
+            continue;
+        }
+
+        if seen.contains(&name) {
+            lints.push(Lint::new(
+                LintKind::DuplicateField(name.to_string()),
+                offset,
+            ));
+        } else {
+            seen.push(name);
+        }
+        if known.is_some_and(|known| !known.contains(&name)) {
+            lints.push(Lint::new(LintKind::UnknownField(name.to_string()), offset));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lint_messy_file() {
+        let text = "module Base
+{
+  recipe Make Mildew Cure
+  {
+    GardeningSprayEmpty,
+    mapper:bucketEmpty,
+    mapper:bucketEmpty,
+    fluid:Water=0.5,
+
+    Result:GardeningSprayMilk,
+    Time:-40.0,
+    Category:Farming,
+    OnGiveXP:Give10FarmingXP,
+  }
+
+  item RedRadish
+  {
+    Type = Food,
+    Weight = 0.1,
+    Weight = 0.2,
+    DisplayName: Red Radish,
+    DisplayName: Radish,
+  }
+
+  recipe Make Cake Batter
+  {
+    Flour,
+
+    Result:CakeBatter,
+    Time:50.0,
+    Time:60.0,
+    Category:Cooking,
+  }
+
+  recipe Broken
+  {
+    Flour,
+  }
+}
+
+module Unfinished
+{
+  recipe Half Done
+  {
+";
+        let expected = vec![
+            Lint::new(
+                LintKind::InvalidTime,
+                text.find("recipe Make Mildew").unwrap(),
+            ),
+            Lint::new(
+                LintKind::UnknownField(String::from("OnGiveXP")),
+                text.find("OnGiveXP").unwrap(),
+            ),
+            Lint::new(
+                LintKind::DuplicateField(String::from("Weight")),
+                text.find("Weight = 0.2").unwrap(),
+            ),
+            Lint::new(
+                LintKind::DuplicateField(String::from("DisplayName")),
+                text.find("DisplayName: Radish").unwrap(),
+            ),
+            Lint::new(
+                LintKind::DuplicateField(String::from("Time")),
+                text.find("Time:60.0").unwrap(),
+            ),
+            Lint::new(
+                LintKind::InvalidDefinition,
+                text.find("recipe Broken").unwrap(),
+            ),
+            Lint::new(
+                LintKind::UnterminatedBlock,
+                text.find("module Unfinished").unwrap(),
+            ),
+        ];
+
+        assert_eq!(expected, lint(text));
+    }
+
+    #[test]
+    fn clean_file_has_no_lints() {
+        let text = "module Base
+{
+  recipe Make Cake Batter
+  {
+    Flour,
+
+    Result:CakeBatter,
+    Time:50.0,
+    Category:Cooking,
+  }
+}
+";
+
+        assert!(lint(text).is_empty());
+    }
+}
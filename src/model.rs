@@ -0,0 +1,138 @@
+use nom::{
+    branch::alt, character::complete::multispace1, combinator::map, error::ParseError,
+    multi::separated_list0, number::complete::float, IResult, Parser,
+};
+
+use crate::{field_value_nl, named_block, normalize_name, text_value, Named};
+
+/// A `model` block, naming the mesh and texture an item is drawn with.
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Model {
+    name: String,
+    mesh: Option<String>,
+    texture: Option<String>,
+    scale: Option<f32>,
+}
+
+#[derive(Default)]
+struct ModelBody<'a> {
+    mesh: Option<&'a str>,
+    texture: Option<&'a str>,
+    scale: Option<f32>,
+}
+
+enum ModelField<'a> {
+    Mesh(&'a str),
+    Texture(&'a str),
+    Scale(f32),
+}
+
+impl Model {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            mesh: None,
+            texture: None,
+            scale: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Path of the mesh, relative to the game's model directory.
+    pub fn mesh(&self) -> Option<&str> {
+        self.mesh.as_deref()
+    }
+
+    /// Path of the texture, relative to the game's texture directory.
+    pub fn texture(&self) -> Option<&str> {
+        self.texture.as_deref()
+    }
+
+    pub fn scale(&self) -> Option<f32> {
+        self.scale
+    }
+}
+
+impl Named for Model {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<'a> From<(&'a str, ModelBody<'a>)> for Model {
+    fn from((name, body): (&'a str, ModelBody<'a>)) -> Self {
+        Model {
+            name: normalize_name(name),
+            mesh: body.mesh.map(str::to_string),
+            texture: body.texture.map(str::to_string),
+            scale: body.scale,
+        }
+    }
+}
+
+fn model_field<'a, E>(input: &'a str) -> IResult<&'a str, ModelField<'a>, E>
+where
+    E: ParseError<&'a str>,
+{
+    alt((
+        map(field_value_nl("mesh", "=", text_value), ModelField::Mesh),
+        map(
+            field_value_nl("texture", "=", text_value),
+            ModelField::Texture,
+        ),
+        map(field_value_nl("scale", "=", float), ModelField::Scale),
+    ))(input)
+}
+
+fn model_body<'a, E>(input: &'a str) -> IResult<&'a str, ModelBody<'a>, E>
+where
+    E: ParseError<&'a str>,
+{
+    let (input, fields) = separated_list0(multispace1, model_field)(input)?;
+
+    let mut body = ModelBody::default();
+    for field in fields {
+        match field {
+            ModelField::Mesh(value) => body.mesh = Some(value),
+            ModelField::Texture(value) => body.texture = Some(value),
+            ModelField::Scale(value) => body.scale = Some(value),
+        }
+    }
+    Ok((input, body))
+}
+
+pub fn model<'a, E>(input: &'a str) -> IResult<&'a str, Model, E>
+where
+    E: ParseError<&'a str>,
+{
+    Parser::into(named_block("model", model_body)).parse(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Result<T> = IResult<&'static str, T, nom::error::Error<&'static str>>;
+
+    #[test]
+    fn parse_model() {
+        let model_text = "model Radish
+{
+    mesh = WorldItems/Radish,
+    texture = WorldItems/Radish,
+    scale = 0.4,
+}";
+
+        let model_res: Result<Model> = model(model_text);
+        let (_, actual) = model_res.expect("failed to parse model");
+
+        assert_eq!("Radish", actual.name());
+        assert_eq!(Some("WorldItems/Radish"), actual.mesh());
+        assert_eq!(Some("WorldItems/Radish"), actual.texture());
+        assert_eq!(Some(0.4), actual.scale());
+    }
+}
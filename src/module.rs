@@ -1,29 +1,297 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
 use nom::{error::ParseError, Parser};
 
-use crate::named_block_repeated;
+use crate::{named_block_repeated, normalize_name, Definition, Named, Recipe};
 
+#[non_exhaustive]
 pub struct Module<Definitions> {
     pub blocks: Vec<ModuleBlock<Definitions>>,
 }
 
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub struct ModuleBlock<Definitions> {
+    /// The name as written, dots included: `module My.Big.Mod` is named
+    /// `My.Big.Mod`, not split into a path.
     pub name: String,
+    /// Modules listed in the `imports` block, whose names may be used
+    /// without qualification.
+    pub imports: Vec<String>,
+    /// The module's own `VERSION = n,` pseudo-field, if it has one.
+    pub version: Option<u32>,
     pub definitions: Vec<Definitions>,
 }
 
+impl<Definitions> Module<Definitions> {
+    pub fn new(blocks: Vec<ModuleBlock<Definitions>>) -> Self {
+        Self { blocks }
+    }
+}
+
 impl<Definitions> ModuleBlock<Definitions> {
     pub fn new(name: impl Into<String>, definitions: Vec<Definitions>) -> Self {
         Self {
             name: name.into(),
+            imports: Vec::new(),
+            version: None,
             definitions,
         }
     }
+
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Start building a module in code, one definition at a time.
+    pub fn builder(name: impl Into<String>) -> ModuleBlockBuilder<Definitions> {
+        ModuleBlockBuilder::new(name)
+    }
+
+    /// Add a definition after the existing ones.
+    pub fn push(&mut self, definition: Definitions) {
+        self.definitions.push(definition);
+    }
+}
+
+/// Builds a [`ModuleBlock`] in code:
+///
+/// ```
+/// use pz_data::{ModuleBlock, Recipe};
+///
+/// let block = ModuleBlock::builder("Base")
+///     .import("Farming")
+///     .definition(Recipe::new("Make Cake Batter", ["Flour"], "CakeBatter", 50.0, "Cooking", false))
+///     .build();
+/// assert_eq!(1, block.definitions.len());
+/// ```
+pub struct ModuleBlockBuilder<Definitions> {
+    block: ModuleBlock<Definitions>,
+}
+
+impl<Definitions> ModuleBlockBuilder<Definitions> {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            block: ModuleBlock::new(name, Vec::new()),
+        }
+    }
+
+    pub fn import(mut self, module: impl Into<String>) -> Self {
+        self.block.imports.push(module.into());
+        self
+    }
+
+    pub fn version(mut self, version: u32) -> Self {
+        self.block.version = Some(version);
+        self
+    }
+
+    pub fn definition(mut self, definition: Definitions) -> Self {
+        self.block.push(definition);
+        self
+    }
+
+    pub fn build(self) -> ModuleBlock<Definitions> {
+        self.block
+    }
+}
+
+/// Names of the definitions that differ between two versions of a module.
+#[derive(Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BlockDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+impl BlockDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl<Definitions: Named + PartialEq> ModuleBlock<Definitions> {
+    /// Compare definitions by name against `other`. Definitions only in
+    /// `other` are added, those only in `self` are removed, and those in
+    /// both whose contents differ are changed.
+    pub fn diff(&self, other: &ModuleBlock<Definitions>) -> BlockDiff {
+        let mut diff = BlockDiff::default();
+        for definition in &self.definitions {
+            match other.find(definition.name()) {
+                Some(theirs) if theirs != definition => {
+                    diff.changed.push(definition.name().to_string())
+                }
+                Some(_) => {}
+                None => diff.removed.push(definition.name().to_string()),
+            }
+        }
+        for definition in &other.definitions {
+            if self.find(definition.name()).is_none() {
+                diff.added.push(definition.name().to_string());
+            }
+        }
+        diff
+    }
+
+    fn find(&self, name: &str) -> Option<&Definitions> {
+        self.definitions.iter().find(|d| d.name() == name)
+    }
+}
+
+impl<Definitions: Named> ModuleBlock<Definitions> {
+    /// Key the definitions by name. When several share a name the last one
+    /// wins, matching how the game lets later definitions override earlier
+    /// ones.
+    pub fn into_map(self) -> HashMap<String, Definitions> {
+        self.definitions
+            .into_iter()
+            .map(|definition| (definition.name().to_string(), definition))
+            .collect()
+    }
+
+    /// Group the definitions by name, keeping every duplicate in source
+    /// order.
+    pub fn into_multimap(self) -> BTreeMap<String, Vec<Definitions>> {
+        let mut map: BTreeMap<String, Vec<Definitions>> = BTreeMap::new();
+        for definition in self.definitions {
+            map.entry(definition.name().to_string())
+                .or_default()
+                .push(definition);
+        }
+        map
+    }
+
+    /// Like `into_map`, but borrowing the definitions.
+    pub fn to_map(&self) -> HashMap<&str, &Definitions> {
+        self.definitions
+            .iter()
+            .map(|definition| (definition.name(), definition))
+            .collect()
+    }
+}
+
+impl ModuleBlock<Definition> {
+    /// The recipes in the module that aren't marked `Obsolete`, which are
+    /// the ones the game offers for crafting.
+    pub fn recipes_active(&self) -> impl Iterator<Item = &Recipe> {
+        self.definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                Definition::Recipe(recipe) if !recipe.obsolete() => Some(recipe.as_ref()),
+                _ => None,
+            })
+    }
+}
+
+/// How `merge_modules` treats a definition that overrides one of the same
+/// name.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// The overriding definition replaces the original outright.
+    #[default]
+    Replace,
+    /// As `Replace`, but extra fields only the original has are kept.
+    MergeExtra,
+}
+
+/// Apply `overlay` on top of `base`, the way the game applies a mod's
+/// module over the one it patches. Definitions in `overlay` replace those
+/// of the same kind and name in `base` in place; the rest are appended.
+/// The name, imports and version of `base` are kept.
+pub fn merge_modules(
+    base: ModuleBlock<Definition>,
+    overlay: ModuleBlock<Definition>,
+    policy: MergePolicy,
+) -> ModuleBlock<Definition> {
+    let mut merged = base;
+    for mut definition in overlay.definitions {
+        let original = merged
+            .definitions
+            .iter_mut()
+            .find(|d| d.same_kind(&definition) && d.name() == definition.name());
+        match original {
+            Some(original) => {
+                if policy == MergePolicy::MergeExtra {
+                    merge_extra(original.extra_mut(), definition.extra_mut());
+                }
+                *original = definition;
+            }
+            None => merged.definitions.push(definition),
+        }
+    }
+    merged
+}
+
+/// Fill `overlay` with the entries only `base` has, ahead of its own so
+/// that the original field order is mostly preserved.
+fn merge_extra(base: &mut Vec<(String, String)>, overlay: &mut Vec<(String, String)>) {
+    let mut extra = std::mem::take(base);
+    for (name, value) in &mut extra {
+        if let Some(index) = overlay.iter().position(|(n, _)| n == name) {
+            *value = overlay.remove(index).1;
+        }
+    }
+    extra.append(overlay);
+    *overlay = extra;
 }
 
 impl<'a, T> From<(&'a str, Vec<T>)> for ModuleBlock<T> {
     fn from((name, items): (&'a str, Vec<T>)) -> Self {
-        ModuleBlock::new(name, items)
+        ModuleBlock::new(normalize_name(name), items)
+    }
+}
+
+impl<Definitions: fmt::Display> fmt::Display for ModuleBlock<Definitions> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "module {}", self.name)?;
+        writeln!(f, "{{")?;
+        if !self.imports.is_empty() {
+            writeln!(f, "    imports")?;
+            writeln!(f, "    {{")?;
+            for import in &self.imports {
+                writeln!(f, "        {},", import)?;
+            }
+            writeln!(f, "    }}")?;
+            writeln!(f)?;
+        }
+        if let Some(version) = self.version {
+            writeln!(f, "    VERSION = {},", version)?;
+            if !self.definitions.is_empty() {
+                writeln!(f)?;
+            }
+        }
+        for (i, definition) in self.definitions.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for line in definition.to_string().lines() {
+                if line.is_empty() {
+                    writeln!(f)?;
+                } else {
+                    writeln!(f, "    {}", line)?;
+                }
+            }
+        }
+        write!(f, "}}")
+    }
+}
+
+impl<Definitions: fmt::Display> fmt::Display for Module<Definitions> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, block) in self.blocks.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+                writeln!(f)?;
+            }
+            write!(f, "{}", block)?;
+        }
+        Ok(())
     }
 }
 
@@ -39,7 +307,7 @@ where
 mod tests {
     use nom::{bytes::complete::tag, IResult, Parser};
 
-    use crate::named_block_repeated;
+    use crate::{named_block_repeated, recipe, recipe_with_options, ParseOptions, Recipe};
 
     use super::*;
 
@@ -50,6 +318,8 @@ mod tests {
         let test_text = "module Base { foo foo foo }";
         let expected = ModuleBlock {
             name: String::from("Base"),
+            imports: vec![],
+            version: None,
             definitions: vec!["foo", "foo", "foo"],
         };
 
@@ -59,4 +329,303 @@ mod tests {
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn diff_modules_by_definition_name() {
+        let mildew_cure = Recipe::new(
+            "Make Mildew Cure",
+            vec!["GardeningSprayEmpty", "Base.Milk"],
+            "GardeningSprayMilk",
+            40.0,
+            "Farming",
+            true,
+        );
+        let slower_mildew_cure = Recipe::new(
+            "Make Mildew Cure",
+            vec!["GardeningSprayEmpty", "Base.Milk"],
+            "GardeningSprayMilk",
+            80.0,
+            "Farming",
+            true,
+        );
+        let cake_batter = Recipe::new(
+            "Make Cake Batter",
+            vec!["Flour"],
+            "CakeBatter",
+            50.0,
+            "Cooking",
+            false,
+        );
+        let base = ModuleBlock::new("Base", vec![mildew_cure]);
+        let modded = ModuleBlock::new("Base", vec![slower_mildew_cure, cake_batter]);
+
+        let expected = BlockDiff {
+            added: vec![String::from("Make Cake Batter")],
+            removed: vec![],
+            changed: vec![String::from("Make Mildew Cure")],
+        };
+
+        assert_eq!(expected, base.diff(&modded));
+    }
+
+    #[test]
+    fn map_by_name_keeps_last_duplicate() {
+        let mildew_cure = Recipe::new(
+            "Make Mildew Cure",
+            vec!["GardeningSprayEmpty", "Base.Milk"],
+            "GardeningSprayMilk",
+            40.0,
+            "Farming",
+            true,
+        );
+        let slower_mildew_cure = Recipe::new(
+            "Make Mildew Cure",
+            vec!["GardeningSprayEmpty", "Base.Milk"],
+            "GardeningSprayMilk",
+            80.0,
+            "Farming",
+            true,
+        );
+        let block = ModuleBlock::new("Base", vec![mildew_cure, slower_mildew_cure]);
+
+        let borrowed = block.to_map();
+        assert_eq!(1, borrowed.len());
+        assert_eq!(80.0, borrowed["Make Mildew Cure"].time());
+
+        let owned = block.into_map();
+        assert_eq!(1, owned.len());
+        assert_eq!(80.0, owned["Make Mildew Cure"].time());
+    }
+
+    #[test]
+    fn multimap_keeps_every_duplicate() {
+        let mildew_cure = Recipe::new(
+            "Make Mildew Cure",
+            vec!["GardeningSprayEmpty", "Base.Milk"],
+            "GardeningSprayMilk",
+            40.0,
+            "Farming",
+            true,
+        );
+        let slower_mildew_cure = Recipe::new(
+            "Make Mildew Cure",
+            vec!["GardeningSprayEmpty", "Base.Milk"],
+            "GardeningSprayMilk",
+            80.0,
+            "Farming",
+            true,
+        );
+        let block = ModuleBlock::new("Base", vec![mildew_cure, slower_mildew_cure]);
+
+        let actual = block.into_multimap();
+
+        assert_eq!(1, actual.len());
+        let times: Vec<f32> = actual["Make Mildew Cure"]
+            .iter()
+            .map(Recipe::time)
+            .collect();
+        assert_eq!(vec![40.0, 80.0], times);
+    }
+
+    fn recipe_with_extra(time: &str, extra: &str) -> Definition {
+        let text = format!(
+            "recipe Make Mildew Cure
+{{
+    GardeningSprayEmpty,
+
+    Result:GardeningSprayMilk,
+    Time:{time},
+    Category:Farming,
+{extra}}}"
+        );
+        let options = ParseOptions {
+            collect_unknown_fields: true,
+            ..ParseOptions::default()
+        };
+        let recipe_res: IResult<&str, Recipe, nom::error::Error<&str>> =
+            recipe_with_options(options)(&text);
+        let (_, recipe) = recipe_res.expect("failed to parse recipe");
+        Definition::Recipe(Box::new(recipe))
+    }
+
+    #[test]
+    fn merge_overrides_and_keeps_base_extras() {
+        let base = || {
+            ModuleBlock::new(
+                "Base",
+                vec![recipe_with_extra(
+                    "40.0",
+                    "    IsHidden:true,\n    Tooltip:Old,\n",
+                )],
+            )
+        };
+        let overlay = || {
+            ModuleBlock::new(
+                "Base",
+                vec![recipe_with_extra(
+                    "80.0",
+                    "    Tooltip:New,\n    Icon:Spray,\n",
+                )],
+            )
+        };
+
+        let replaced = merge_modules(base(), overlay(), MergePolicy::Replace);
+        assert_eq!(overlay(), replaced);
+
+        let merged = merge_modules(base(), overlay(), MergePolicy::MergeExtra);
+        let expected = ModuleBlock::new(
+            "Base",
+            vec![recipe_with_extra(
+                "80.0",
+                "    IsHidden:true,\n    Tooltip:New,\n    Icon:Spray,\n",
+            )],
+        );
+        assert_eq!(expected, merged);
+    }
+
+    #[test]
+    fn active_recipes_skip_obsolete_ones() {
+        let module_text = "module Base
+{
+    recipe Make Mildew Cure
+    {
+        GardeningSprayEmpty,
+
+        Result:GardeningSprayMilk,
+        Time:40.0,
+        Category:Farming,
+        Obsolete:true,
+    }
+
+    recipe Make Cake Batter
+    {
+        Flour,
+
+        Result:CakeBatter,
+        Time:50.0,
+        Category:Cooking,
+    }
+}";
+
+        let block = crate::parse_file::<nom::error::Error<&str>>(module_text)
+            .expect("failed to parse module")
+            .remove(0);
+        let active: Vec<&str> = block.recipes_active().map(Recipe::name).collect();
+
+        assert_eq!(vec!["Make Cake Batter"], active);
+    }
+
+    #[test]
+    fn build_module_and_display() {
+        let mut pushed = ModuleBlock::new("Base", vec![]);
+        pushed.push(Recipe::new(
+            "Make Mildew Cure",
+            vec!["GardeningSprayEmpty", "Base.Milk"],
+            "GardeningSprayMilk",
+            40.0,
+            "Farming",
+            true,
+        ));
+        pushed.push(Recipe::new(
+            "Make Cake Batter",
+            vec!["Flour"],
+            "CakeBatter",
+            50.0,
+            "Cooking",
+            false,
+        ));
+        let built = ModuleBlock::builder("Base")
+            .definition(Recipe::new(
+                "Make Mildew Cure",
+                vec!["GardeningSprayEmpty", "Base.Milk"],
+                "GardeningSprayMilk",
+                40.0,
+                "Farming",
+                true,
+            ))
+            .definition(Recipe::new(
+                "Make Cake Batter",
+                vec!["Flour"],
+                "CakeBatter",
+                50.0,
+                "Cooking",
+                false,
+            ))
+            .build();
+
+        assert_eq!(pushed, built);
+        let text = built.to_string();
+        assert!(text.starts_with("module Base\n{\n    recipe Make Mildew Cure\n"));
+        assert!(text.contains("    }\n\n    recipe Make Cake Batter\n"));
+        assert!(text.ends_with("    }\n}"));
+    }
+
+    #[test]
+    fn display_round_trips_module_of_recipes() {
+        let expected = ModuleBlock::new(
+            "Base",
+            vec![
+                Recipe::new(
+                    "Make Mildew Cure",
+                    vec!["GardeningSprayEmpty", "Base.Milk"],
+                    "GardeningSprayMilk",
+                    40.0,
+                    "Farming",
+                    true,
+                ),
+                Recipe::new(
+                    "Make Cake Batter",
+                    vec!["Flour"],
+                    "CakeBatter",
+                    50.0,
+                    "Cooking",
+                    false,
+                ),
+            ],
+        );
+
+        let text = expected.to_string();
+        let block_res: IResult<&str, ModuleBlock<Recipe>, nom::error::Error<&str>> =
+            module(recipe).parse(&text);
+        let (_, actual) = block_res.expect("failed to parse displayed module");
+
+        assert_eq!(expected, actual);
+        assert!(text.starts_with("module Base\n{\n    recipe Make Mildew Cure\n    {\n"));
+        assert!(text.contains("    }\n\n    recipe Make Cake Batter\n"));
+    }
+
+    #[test]
+    fn display_round_trips_empty_module() {
+        let empty: ModuleBlock<Recipe> = ModuleBlock::new("Base", vec![]);
+        let versioned: ModuleBlock<Recipe> = ModuleBlock::new("Farming", vec![]).with_version(2);
+
+        for expected in [empty, versioned] {
+            let text = expected.to_string();
+            let actual = crate::parse_file::<nom::error::Error<&str>>(&text)
+                .expect("failed to parse displayed module")
+                .remove(0);
+
+            assert_eq!(expected.name, actual.name);
+            assert_eq!(expected.version, actual.version);
+            assert!(actual.definitions.is_empty());
+        }
+    }
+
+    #[test]
+    fn build_empty_module_and_parse_display() {
+        let built: ModuleBlock<Recipe> = ModuleBlock::builder("Base")
+            .import("Farming")
+            .version(2)
+            .build();
+
+        let text = built.to_string();
+        let actual = crate::parse_file::<nom::error::Error<&str>>(&text)
+            .expect("failed to parse displayed module")
+            .remove(0);
+
+        assert_eq!(built.name, actual.name);
+        assert_eq!(built.imports, actual.imports);
+        assert_eq!(built.version, actual.version);
+        assert!(actual.definitions.is_empty());
+    }
 }
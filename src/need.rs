@@ -0,0 +1,57 @@
+use std::fmt;
+
+use nom::{branch::alt, combinator::map, error::ParseError, IResult};
+
+use crate::{identifier1, skill_requirement, SkillReq};
+
+/// One element of a combined `Needs:Cooking:2;Knife,` field: either a skill
+/// level or an item that must be at hand.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Need {
+    Skill(SkillReq),
+    Item(String),
+}
+
+impl fmt::Display for Need {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Need::Skill(skill) => write!(f, "{}", skill),
+            Need::Item(item) => write!(f, "{}", item),
+        }
+    }
+}
+
+pub fn need<'a, E>(input: &'a str) -> IResult<&'a str, Need, E>
+where
+    E: ParseError<&'a str>,
+{
+    alt((
+        map(skill_requirement, Need::Skill),
+        map(identifier1, |item: &str| Need::Item(item.to_string())),
+    ))(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::list_value;
+
+    type Result<T> = IResult<&'static str, T, nom::error::Error<&'static str>>;
+
+    #[test]
+    fn parse_mixed_needs() {
+        let needs_res: Result<Vec<Need>> = list_value(need)("Cooking:2;Base.KitchenKnife,");
+        let (rest, actual) = needs_res.expect("failed to parse needs");
+
+        assert_eq!(",", rest);
+        assert_eq!(
+            vec![
+                Need::Skill(SkillReq::new("Cooking", 2)),
+                Need::Item(String::from("Base.KitchenKnife")),
+            ],
+            actual
+        );
+    }
+}
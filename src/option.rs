@@ -0,0 +1,223 @@
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{alphanumeric1, space0},
+    combinator::{map, opt},
+    error::ParseError,
+    multi::separated_list0,
+    sequence::{delimited, pair, preceded},
+    IResult, Parser,
+};
+
+use crate::{
+    field_value_nl, leading_comments, line_end, named_block_with_options, normalize_name,
+    separator, text_value, trailing_comments, Named, ParseOptions,
+};
+
+/// An `option` block from a sandbox or configuration script. Named with a
+/// trailing underscore so as not to clash with `std::option::Option`.
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Option_ {
+    name: String,
+    option_type: Option<String>,
+    default: Option<String>,
+    page: Option<String>,
+    extra: Vec<(String, String)>,
+    leading_comments: Vec<String>,
+}
+
+#[derive(Default)]
+struct OptionBody<'a> {
+    option_type: Option<&'a str>,
+    default: Option<&'a str>,
+    page: Option<&'a str>,
+    extra: Vec<(&'a str, &'a str)>,
+}
+
+enum OptionField<'a> {
+    Type(&'a str),
+    Default(&'a str),
+    Page(&'a str),
+    Unknown(&'a str, &'a str),
+}
+
+impl Option_ {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            option_type: None,
+            default: None,
+            page: None,
+            extra: Vec::new(),
+            leading_comments: Vec::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The kind of value the option holds, such as `boolean` or `integer`.
+    pub fn option_type(&self) -> Option<&str> {
+        self.option_type.as_deref()
+    }
+
+    /// The default value, as written.
+    pub fn default_value(&self) -> Option<&str> {
+        self.default.as_deref()
+    }
+
+    /// The settings page the option is shown on.
+    pub fn page(&self) -> Option<&str> {
+        self.page.as_deref()
+    }
+
+    /// Fields without a typed accessor, such as `min` and `max`, in source
+    /// order.
+    pub fn extra(&self) -> &[(String, String)] {
+        &self.extra
+    }
+
+    pub(crate) fn extra_mut(&mut self) -> &mut Vec<(String, String)> {
+        &mut self.extra
+    }
+
+    /// Comments directly above the option. Only populated when parsing with
+    /// `attach_comments`.
+    pub fn leading_comments(&self) -> &[String] {
+        &self.leading_comments
+    }
+
+    pub(crate) fn set_leading_comments(&mut self, comments: Vec<String>) {
+        self.leading_comments = comments;
+    }
+}
+
+impl Named for Option_ {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<'a> From<(&'a str, OptionBody<'a>)> for Option_ {
+    fn from((name, body): (&'a str, OptionBody<'a>)) -> Self {
+        Option_ {
+            name: normalize_name(name),
+            option_type: body.option_type.map(str::to_string),
+            default: body.default.map(str::to_string),
+            page: body.page.map(str::to_string),
+            extra: body
+                .extra
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            leading_comments: Vec::new(),
+        }
+    }
+}
+
+fn unknown_field<'a, E>(input: &'a str) -> IResult<&'a str, OptionField<'a>, E>
+where
+    E: ParseError<&'a str>,
+{
+    let (input, name) = preceded(space0, alphanumeric1)(input)?;
+    let (input, _) = delimited(space0, tag("="), space0)(input)?;
+    let (input, value) = map(opt(text_value), Option::unwrap_or_default)(input)?;
+    let (input, _) = pair(opt(tag(",")), line_end)(input)?;
+    Ok((input, OptionField::Unknown(name, value)))
+}
+
+fn option_field<'a, E>(input: &'a str) -> IResult<&'a str, OptionField<'a>, E>
+where
+    E: ParseError<&'a str>,
+{
+    alt((
+        map(field_value_nl("type", "=", text_value), OptionField::Type),
+        map(
+            field_value_nl("default", "=", text_value),
+            OptionField::Default,
+        ),
+        map(field_value_nl("page", "=", text_value), OptionField::Page),
+        unknown_field,
+    ))(input)
+}
+
+fn option_body<'a, E>(
+    options: ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, OptionBody<'a>, E>
+where
+    E: ParseError<&'a str>,
+{
+    move |input: &'a str| {
+        let (input, fields) = delimited(
+            leading_comments(options),
+            separated_list0(separator(options), option_field),
+            trailing_comments(options),
+        )(input)?;
+
+        let mut body = OptionBody::default();
+        for field in fields {
+            match field {
+                OptionField::Type(value) => body.option_type = Some(value),
+                OptionField::Default(value) => body.default = Some(value),
+                OptionField::Page(value) => body.page = Some(value),
+                OptionField::Unknown(name, value) => body.extra.push((name, value)),
+            }
+        }
+        Ok((input, body))
+    }
+}
+
+pub fn option<'a, E>(input: &'a str) -> IResult<&'a str, Option_, E>
+where
+    E: ParseError<&'a str>,
+{
+    option_with_options(ParseOptions::default())(input)
+}
+
+pub fn option_with_options<'a, E>(
+    options: ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Option_, E>
+where
+    E: ParseError<&'a str>,
+{
+    move |input: &'a str| {
+        Parser::into(named_block_with_options(
+            "option",
+            options,
+            option_body(options),
+        ))
+        .parse(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Result<T> = IResult<&'static str, T, nom::error::Error<&'static str>>;
+
+    #[test]
+    fn parse_option_block() {
+        let option_text = "option ZombieLore.Speed
+{
+    type = enum,
+    default = 2,
+    page = ZombieLore,
+    numValues = 3,
+}";
+
+        let option_res: Result<Option_> = option(option_text);
+        let (_, actual) = option_res.expect("failed to parse option");
+
+        assert_eq!("ZombieLore.Speed", actual.name());
+        assert_eq!(Some("enum"), actual.option_type());
+        assert_eq!(Some("2"), actual.default_value());
+        assert_eq!(Some("ZombieLore"), actual.page());
+        assert_eq!(
+            &[(String::from("numValues"), String::from("3"))],
+            actual.extra()
+        );
+    }
+}
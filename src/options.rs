@@ -0,0 +1,37 @@
+/// Switches that relax the parser. The default is strict and accepts only
+/// the canonical script format; start from `ParseOptions::default()` and
+/// set the switches you need.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ParseOptions {
+    /// Accept header fields whose terminating comma has been left off,
+    /// provided nothing else follows on the same line.
+    pub allow_trailing_comma: bool,
+    /// Keep fields the parser doesn't know about instead of failing.
+    pub collect_unknown_fields: bool,
+    /// Accept `TRUE`/`False` in any case as well as `1` and `0` for
+    /// boolean fields.
+    pub lenient_bools: bool,
+    /// Skip `/* ... */` and `// ...` comments wherever whitespace is
+    /// allowed between definitions and fields.
+    pub allow_comments: bool,
+    /// Keep the comments directly above a definition on that definition
+    /// instead of discarding them. Implies `allow_comments`.
+    pub attach_comments: bool,
+    /// Allow line breaks inside quoted values, for tooltips written over
+    /// several lines.
+    pub multiline_strings: bool,
+    /// Replace `\r\n` with `\n` inside multi-line values, so that CRLF
+    /// and LF files produce identical strings.
+    pub normalize_line_endings: bool,
+    /// Accept `;` as well as `,` at the end of a recipe header field. The
+    /// `;` separators inside list values such as `SkillRequired` are still
+    /// read as part of the list.
+    pub semicolon_terminators: bool,
+}
+
+impl ParseOptions {
+    pub(crate) fn comments_allowed(&self) -> bool {
+        self.allow_comments || self.attach_comments
+    }
+}
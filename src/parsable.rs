@@ -0,0 +1,129 @@
+use nom::{
+    error::{ContextError, ErrorKind, ParseError},
+    IResult,
+};
+
+use crate::{
+    item_with_options, parse_document, recipe_with_options, Item, ParseOptions, Recipe, SymbolKind,
+};
+
+/// A block type that can be picked out of a file by its tag.
+pub trait ParsableBlock: Sized {
+    /// The word that opens the block, such as `recipe`.
+    const TAG: &'static str;
+
+    /// Parse one whole block, tag included.
+    fn parse_block<'a, E>(input: &'a str, options: ParseOptions) -> IResult<&'a str, Self, E>
+    where
+        E: ParseError<&'a str> + ContextError<&'a str>;
+}
+
+impl ParsableBlock for Recipe {
+    const TAG: &'static str = "recipe";
+
+    fn parse_block<'a, E>(input: &'a str, options: ParseOptions) -> IResult<&'a str, Self, E>
+    where
+        E: ParseError<&'a str> + ContextError<&'a str>,
+    {
+        recipe_with_options(options)(input)
+    }
+}
+
+impl ParsableBlock for Item {
+    const TAG: &'static str = "item";
+
+    fn parse_block<'a, E>(input: &'a str, options: ParseOptions) -> IResult<&'a str, Self, E>
+    where
+        E: ParseError<&'a str> + ContextError<&'a str>,
+    {
+        item_with_options(options)(input)
+    }
+}
+
+/// Every `T` block in `input`, at any depth, in file order. Blocks of
+/// other types are skipped without being parsed, so only the `T` blocks
+/// need to be valid, though every block must be closed.
+pub fn parse_all<'a, T, E>(input: &'a str) -> Result<Vec<T>, nom::Err<E>>
+where
+    T: ParsableBlock,
+    E: ParseError<&'a str> + ContextError<&'a str>,
+{
+    let kind = SymbolKind::from(T::TAG);
+    let document = parse_document(input);
+    if let Some(offset) = document.unparsed {
+        return Err(nom::Err::Error(E::from_error_kind(
+            &input[offset..],
+            ErrorKind::Eof,
+        )));
+    }
+    document
+        .symbols
+        .into_iter()
+        .filter(|symbol| symbol.kind == kind)
+        .map(|symbol| {
+            let (_, block) = T::parse_block(&input[symbol.full_range], ParseOptions::default())?;
+            Ok(block)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Error = nom::error::Error<&'static str>;
+
+    const FILE_TEXT: &str = "
+module Base
+{
+  recipe Make Mildew Cure
+  {
+    GardeningSprayEmpty,
+
+    Result:GardeningSprayMilk,
+    Time:40.0,
+    Category:Farming,
+  }
+
+  item RedRadish
+  {
+    Type = Food
+  }
+}
+
+module Farming
+{
+  recipe Open Seed Packet
+  {
+    RadishBagSeed,
+
+    Result:RadishSeed=50,
+    Time:20.0,
+    Category:Farming,
+  }
+}
+";
+
+    #[test]
+    fn parse_all_recipes() {
+        let recipes = parse_all::<Recipe, Error>(FILE_TEXT).expect("failed to parse recipes");
+        let names: Vec<&str> = recipes.iter().map(Recipe::name).collect();
+
+        assert_eq!(vec!["Make Mildew Cure", "Open Seed Packet"], names);
+    }
+
+    #[test]
+    fn parse_all_items() {
+        let items = parse_all::<Item, Error>(FILE_TEXT).expect("failed to parse items");
+        let names: Vec<&str> = items.iter().map(Item::name).collect();
+
+        assert_eq!(vec!["RedRadish"], names);
+    }
+
+    #[test]
+    fn unclosed_block_is_an_error() {
+        let actual = parse_all::<Recipe, Error>("module Base\n{\n");
+
+        assert!(actual.is_err());
+    }
+}
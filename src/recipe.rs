@@ -1,50 +1,636 @@
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt,
+    hash::{Hash, Hasher},
+};
+
 use nom::{
+    branch::alt,
     bytes::complete::tag,
-    character::complete::{alphanumeric1, multispace1},
-    error::ParseError,
-    multi::separated_list1,
+    character::complete::{alphanumeric1, multispace0, multispace1, space0, space1},
+    combinator::{all_consuming, eof, map, opt, value},
+    error::{ContextError, ErrorKind, ParseError, VerboseError},
+    multi::many0,
     number::complete::float,
-    sequence::terminated,
+    sequence::{delimited, pair, preceded, terminated, tuple},
     IResult, Parser,
 };
 
-use crate::{bool_value, field_value, identifier1, named_block};
+use crate::{
+    block::string_with_spaces_delimited_by_open_brace, bool_value_with, field_terminator,
+    field_value_terminated, identifier1, ingredient, ingredient::leading_count_ingredient,
+    leading_comments, line_end, list_value, named_block_with_options, need, normalize_name,
+    result_spec, separator, skill_requirement, text_value_with, time_value, trailing_comments,
+    unnamed_block, unnamed_block_repeated, whitespace0, Amount, AnimNode, Ingredient, Named, Need,
+    ParseOptions, RecipeCategory, ResultSpec, SkillReq,
+};
+
+/// Most recipes have only a handful of ingredients, so with the `smallvec`
+/// feature they are stored inline rather than in a separate allocation.
+#[cfg(feature = "smallvec")]
+type Ingredients = smallvec::SmallVec<[Ingredient; 4]>;
+#[cfg(not(feature = "smallvec"))]
+type Ingredients = Vec<Ingredient>;
 
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, PartialEq)]
+#[non_exhaustive]
 pub struct Recipe {
     name: String,
-    ingredients: Vec<String>,
-    result: String,
+    ingredients: Ingredients,
+    result: ResultSpec,
     time: f32,
-    category: String,
+    category: RecipeCategory,
     need_to_be_learned: bool,
+    skills_required: Vec<SkillReq>,
+    needs: Vec<Need>,
+    tags: Vec<String>,
+    on_create: Option<String>,
+    on_test: Option<String>,
+    anim_node: Option<AnimNode>,
+    sound: Option<String>,
+    prop1: Option<String>,
+    prop2: Option<String>,
+    remove_result_item: Option<bool>,
+    allow_frozen_item: Option<bool>,
+    in_same_inventory: Option<bool>,
+    obsolete: Option<bool>,
+    stop_on_walk: Option<bool>,
+    stop_on_run: Option<bool>,
+    heat: Option<f32>,
+    cooking_heat: Option<f32>,
+    extra: Vec<(String, String)>,
+    leading_comments: Vec<String>,
+    module: Option<String>,
+}
+
+/// Reasons an edit to a [`Recipe`] was rejected.
+#[derive(Debug, PartialEq, Eq)]
+pub enum RecipeError {
+    EmptyName,
+    EmptyResult,
+    InvalidTime,
+}
+
+impl fmt::Display for RecipeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecipeError::EmptyName => write!(f, "recipe name must not be empty"),
+            RecipeError::EmptyResult => write!(f, "recipe result must not be empty"),
+            RecipeError::InvalidTime => write!(f, "recipe time must be a non-negative number"),
+        }
+    }
 }
 
+impl Error for RecipeError {}
+
 struct RecipeBody<'a> {
-    ingredients: Vec<&'a str>,
-    result: &'a str,
+    ingredients: Vec<Ingredient>,
+    result: ResultSpec,
     time: f32,
     category: &'a str,
     need_to_be_learned: bool,
+    skills_required: Vec<SkillReq>,
+    needs: Vec<Need>,
+    tags: Vec<&'a str>,
+    on_create: Option<&'a str>,
+    on_test: Option<&'a str>,
+    anim_node: Option<&'a str>,
+    sound: Option<&'a str>,
+    prop1: Option<&'a str>,
+    prop2: Option<&'a str>,
+    remove_result_item: Option<bool>,
+    allow_frozen_item: Option<bool>,
+    in_same_inventory: Option<bool>,
+    obsolete: Option<bool>,
+    stop_on_walk: Option<bool>,
+    stop_on_run: Option<bool>,
+    heat: Option<f32>,
+    cooking_heat: Option<f32>,
+    extra: Vec<(&'a str, &'a str)>,
+}
+
+enum RecipeField<'a> {
+    Result(ResultSpec),
+    Time(f32),
+    Category(&'a str),
+    NeedToBeLearn(bool),
+    SkillRequired(Vec<SkillReq>),
+    Needs(Vec<Need>),
+    Tags(Vec<&'a str>),
+    OnCreate(&'a str),
+    OnTest(&'a str),
+    AnimNode(&'a str),
+    Sound(&'a str),
+    Prop1(&'a str),
+    Prop2(&'a str),
+    RemoveResultItem(bool),
+    AllowFrozenItem(bool),
+    InSameInventory(bool),
+    Obsolete(bool),
+    StopOnWalk(bool),
+    StopOnRun(bool),
+    Heat(f32),
+    CookingHeat(f32),
+    Unknown(&'a str, &'a str),
 }
 
 impl Recipe {
+    /// `Recipe` is `#[non_exhaustive]`, so outside this crate it is built
+    /// with `new` and the setters rather than a struct literal:
+    ///
+    /// ```
+    /// use pz_data::Recipe;
+    ///
+    /// let recipe = Recipe::new(
+    ///     "Make Mildew Cure",
+    ///     ["GardeningSprayEmpty", "Base.Milk"],
+    ///     "GardeningSprayMilk",
+    ///     40.0,
+    ///     "Farming",
+    ///     true,
+    /// );
+    /// assert_eq!("Make Mildew Cure", recipe.name());
+    /// ```
     pub fn new(
         name: impl Into<String>,
-        ingredients: Vec<String>,
-        result: impl Into<String>,
+        ingredients: impl IntoIterator<Item = impl Into<Ingredient>>,
+        result: impl Into<ResultSpec>,
         time: f32,
-        category: impl Into<String>,
+        category: impl Into<RecipeCategory>,
         need_to_be_learned: bool,
     ) -> Self {
         Self {
             name: name.into(),
-            ingredients,
+            ingredients: ingredients.into_iter().map(Into::into).collect(),
             result: result.into(),
             time,
             category: category.into(),
             need_to_be_learned,
+            skills_required: Vec::new(),
+            needs: Vec::new(),
+            tags: Vec::new(),
+            on_create: None,
+            on_test: None,
+            anim_node: None,
+            sound: None,
+            prop1: None,
+            prop2: None,
+            remove_result_item: None,
+            allow_frozen_item: None,
+            in_same_inventory: None,
+            obsolete: None,
+            stop_on_walk: None,
+            stop_on_run: None,
+            heat: None,
+            cooking_heat: None,
+            extra: Vec::new(),
+            leading_comments: Vec::new(),
+            module: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The name split into words for display. `CamelCase` names such as
+    /// `MakeBandage` get a space before each new word; names that already
+    /// contain spaces are returned as they are.
+    pub fn display_name(&self) -> String {
+        if self.name.contains(char::is_whitespace) {
+            return self.name.clone();
+        }
+        let chars: Vec<char> = self.name.chars().collect();
+        let mut display = String::with_capacity(self.name.len() + 4);
+        for (i, &c) in chars.iter().enumerate() {
+            // A capital starts a word after a lowercase letter, or ends a
+            // run of capitals when a lowercase letter follows it.
+            let starts_word = i > 0
+                && c.is_uppercase()
+                && (chars[i - 1].is_lowercase()
+                    || (chars[i - 1].is_uppercase()
+                        && chars.get(i + 1).is_some_and(|n| n.is_lowercase())));
+            if starts_word {
+                display.push(' ');
+            }
+            display.push(c);
+        }
+        display
+    }
+
+    pub fn ingredients(&self) -> &[Ingredient] {
+        &self.ingredients
+    }
+
+    pub fn result(&self) -> &ResultSpec {
+        &self.result
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// Time in seconds to craft the recipe `quantity` times in a row.
+    pub fn total_time_for(&self, quantity: u32) -> f32 {
+        self.time * quantity as f32
+    }
+
+    /// The recipe's category. Use [`RecipeCategory::as_str`] for the raw
+    /// value as written in the script.
+    pub fn category(&self) -> &RecipeCategory {
+        &self.category
+    }
+
+    pub fn need_to_be_learned(&self) -> bool {
+        self.need_to_be_learned
+    }
+
+    pub fn qualified_ingredients(&self) -> impl Iterator<Item = (Option<&str>, &str)> {
+        self.ingredients.iter().map(Ingredient::qualified_name)
+    }
+
+    /// Whether every ingredient name appears in `available`, ignoring
+    /// counts. Names are compared exactly, so `Base.Milk` and `Milk` are
+    /// different items. Tag and mapper selectors name no particular item and
+    /// are never satisfied.
+    pub fn is_craftable_from(&self, available: &HashSet<String>) -> bool {
+        self.ingredients
+            .iter()
+            .all(|ingredient| ingredient.is_item() && available.contains(ingredient.name()))
+    }
+
+    /// The total needed of each item, summing ingredients that are listed
+    /// more than once. Fractional amounts are rounded up to whole items, and
+    /// tag and mapper selectors are left out since they don't name an item.
+    /// Totals too large for a `u32` are capped at `u32::MAX`.
+    pub fn ingredients_as_counts(&self) -> HashMap<String, u32> {
+        let mut counts = HashMap::new();
+        for ingredient in self.ingredients.iter().filter(|i| i.is_item()) {
+            let count = match ingredient.count() {
+                Amount::Int(count) => count,
+                Amount::Float(amount) => amount.ceil() as u32,
+            };
+            let total = counts.entry(ingredient.name().to_string()).or_insert(0u32);
+            *total = total.saturating_add(count);
+        }
+        counts
+    }
+
+    pub fn skills_required(&self) -> &[SkillReq] {
+        &self.skills_required
+    }
+
+    /// The combined skill and item requirements of a `Needs` field.
+    pub fn needs(&self) -> &[Need] {
+        &self.needs
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    pub fn on_create(&self) -> Option<&str> {
+        self.on_create.as_deref()
+    }
+
+    pub fn on_test(&self) -> Option<&str> {
+        self.on_test.as_deref()
+    }
+
+    pub fn anim_node(&self) -> Option<&AnimNode> {
+        self.anim_node.as_ref()
+    }
+
+    pub fn sound(&self) -> Option<&str> {
+        self.sound.as_deref()
+    }
+
+    pub fn prop1(&self) -> Option<&str> {
+        self.prop1.as_deref()
+    }
+
+    pub fn prop2(&self) -> Option<&str> {
+        self.prop2.as_deref()
+    }
+
+    /// Whether crafting consumes the result item rather than producing
+    /// it. `false` when not given.
+    pub fn remove_result_item(&self) -> bool {
+        self.remove_result_item.unwrap_or(false)
+    }
+
+    /// Whether frozen ingredients may be used. `false` when not given.
+    pub fn allow_frozen_item(&self) -> bool {
+        self.allow_frozen_item.unwrap_or(false)
+    }
+
+    /// Whether all ingredients must be in the same container. `false` when
+    /// not given.
+    pub fn in_same_inventory(&self) -> bool {
+        self.in_same_inventory.unwrap_or(false)
+    }
+
+    /// Whether the recipe has been retired and should be hidden from
+    /// crafting menus. `false` when not given.
+    pub fn obsolete(&self) -> bool {
+        self.obsolete.unwrap_or(false)
+    }
+
+    /// Whether walking interrupts crafting. `false` when not given.
+    pub fn stop_on_walk(&self) -> bool {
+        self.stop_on_walk.unwrap_or(false)
+    }
+
+    /// Whether running interrupts crafting. `false` when not given.
+    pub fn stop_on_run(&self) -> bool {
+        self.stop_on_run.unwrap_or(false)
+    }
+
+    /// How hot the food must be for a cooking recipe, from `Heat`.
+    pub fn heat(&self) -> Option<f32> {
+        self.heat
+    }
+
+    pub fn cooking_heat(&self) -> Option<f32> {
+        self.cooking_heat
+    }
+
+    /// Fields that were not recognized, in source order. Only populated
+    /// when parsing with `collect_unknown_fields`.
+    pub fn extra(&self) -> &[(String, String)] {
+        &self.extra
+    }
+
+    pub(crate) fn extra_mut(&mut self) -> &mut Vec<(String, String)> {
+        &mut self.extra
+    }
+
+    /// The last value of an unrecognized field, as written.
+    pub fn extra_value(&self, name: &str) -> Option<&str> {
+        self.extra
+            .iter()
+            .rev()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// An unrecognized field read as a number. `None` if the field is
+    /// absent or is not a number.
+    pub fn extra_f32(&self, name: &str) -> Option<f32> {
+        self.extra_value(name)?.parse().ok()
+    }
+
+    /// An unrecognized field read as `true` or `false`. `None` if the field
+    /// is absent or is not a bool.
+    pub fn extra_bool(&self, name: &str) -> Option<bool> {
+        self.extra_value(name)?.parse().ok()
+    }
+
+    /// Comments directly above the recipe. Only populated when parsing
+    /// with `attach_comments`.
+    pub fn leading_comments(&self) -> &[String] {
+        &self.leading_comments
+    }
+
+    pub(crate) fn set_leading_comments(&mut self, comments: Vec<String>) {
+        self.leading_comments = comments;
+    }
+
+    /// The module the recipe was defined in. Only set when the recipe was
+    /// parsed as part of a file; `recipe` on its own leaves it `None`.
+    pub fn module(&self) -> Option<&str> {
+        self.module.as_deref()
+    }
+
+    pub(crate) fn set_module(&mut self, module: impl Into<String>) {
+        self.module = Some(module.into());
+    }
+
+    /// Check the invariants the setters enforce, for a recipe built some
+    /// other way such as `Recipe::default()`.
+    pub fn validate(&self) -> Result<(), RecipeError> {
+        if self.name.trim().is_empty() {
+            return Err(RecipeError::EmptyName);
+        }
+        if self.result.item.trim().is_empty() {
+            return Err(RecipeError::EmptyResult);
+        }
+        if !is_valid_time(self.time) {
+            return Err(RecipeError::InvalidTime);
+        }
+        Ok(())
+    }
+
+    pub fn set_name(&mut self, name: impl Into<String>) -> Result<(), RecipeError> {
+        let name = name.into();
+        if name.trim().is_empty() {
+            return Err(RecipeError::EmptyName);
+        }
+        self.name = name;
+        Ok(())
+    }
+
+    pub fn set_result(&mut self, result: impl Into<ResultSpec>) -> Result<(), RecipeError> {
+        let result = result.into();
+        if result.item.trim().is_empty() {
+            return Err(RecipeError::EmptyResult);
+        }
+        self.result = result;
+        Ok(())
+    }
+
+    pub fn set_time(&mut self, time: f32) -> Result<(), RecipeError> {
+        if !is_valid_time(time) {
+            return Err(RecipeError::InvalidTime);
+        }
+        self.time = time;
+        Ok(())
+    }
+
+    pub fn add_ingredient(&mut self, ingredient: impl Into<Ingredient>) {
+        self.ingredients.push(ingredient.into());
+    }
+
+    /// Remove the first ingredient with the given name, returning it if
+    /// there was one.
+    pub fn remove_ingredient(&mut self, name: &str) -> Option<Ingredient> {
+        let index = self.ingredients.iter().position(|i| i.name() == name)?;
+        Some(self.ingredients.remove(index))
+    }
+
+    /// Combine ingredients that share a name into a single entry whose
+    /// count is the sum of the duplicates, keeping first-seen order.
+    pub fn with_merged_ingredients(mut self) -> Recipe {
+        let mut merged = Ingredients::with_capacity(self.ingredients.len());
+        for ingredient in self.ingredients {
+            if !merged.iter_mut().any(|m| m.merge(&ingredient)) {
+                merged.push(ingredient);
+            }
+        }
+        self.ingredients = merged;
+        self
+    }
+
+    /// Put the recipe in a canonical form so that equivalent recipes
+    /// display identically. `Display` already writes the known fields in a
+    /// fixed order, so this sorts the extra fields by name and, when
+    /// `sort_ingredients` is set, the ingredients by name as well.
+    pub fn normalized(mut self, sort_ingredients: bool) -> Recipe {
+        self.extra.sort_by(|a, b| a.0.cmp(&b.0));
+        if sort_ingredients {
+            self.ingredients.sort_by(|a, b| a.name().cmp(b.name()));
+        }
+        self
+    }
+
+    /// Consume the recipe, keeping only its ingredients.
+    pub fn into_ingredients(self) -> Vec<Ingredient> {
+        self.ingredients.into_iter().collect()
+    }
+
+    /// Move the core fields out without cloning, in the same order as the
+    /// arguments to `Recipe::new`. Skills, metadata, extra fields and
+    /// comments are dropped.
+    pub fn into_parts(
+        self,
+    ) -> (
+        String,
+        Vec<Ingredient>,
+        ResultSpec,
+        f32,
+        RecipeCategory,
+        bool,
+    ) {
+        (
+            self.name,
+            self.ingredients.into_iter().collect(),
+            self.result,
+            self.time,
+            self.category,
+            self.need_to_be_learned,
+        )
+    }
+}
+
+/// An empty recipe, for filling in with the setters. It has no name or
+/// result, so it doesn't pass `validate` until those are set.
+impl Default for Recipe {
+    fn default() -> Self {
+        Recipe::new("", Vec::<Ingredient>::new(), "", 0.0, "", false)
+    }
+}
+
+impl fmt::Display for Recipe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for comment in &self.leading_comments {
+            if comment.contains('\n') {
+                writeln!(f, "/* {} */", comment)?;
+            } else {
+                writeln!(f, "// {}", comment)?;
+            }
         }
+        writeln!(f, "recipe {}", self.name)?;
+        writeln!(f, "{{")?;
+        for ingredient in &self.ingredients {
+            writeln!(f, "    {},", ingredient)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "    Result:{},", self.result)?;
+        writeln!(f, "    Time:{},", self.time)?;
+        writeln!(f, "    Category:{},", self.category)?;
+        writeln!(f, "    NeedToBeLearn:{},", self.need_to_be_learned)?;
+        if !self.skills_required.is_empty() {
+            let skills: Vec<String> = self.skills_required.iter().map(|s| s.to_string()).collect();
+            writeln!(f, "    SkillRequired:{},", skills.join(";"))?;
+        }
+        if !self.needs.is_empty() {
+            let needs: Vec<String> = self.needs.iter().map(|n| n.to_string()).collect();
+            writeln!(f, "    Needs:{},", needs.join(";"))?;
+        }
+        if !self.tags.is_empty() {
+            writeln!(f, "    Tags:{},", self.tags.join(";"))?;
+        }
+        let metadata = [
+            ("OnCreate", self.on_create.as_deref()),
+            ("OnTest", self.on_test.as_deref()),
+            ("AnimNode", self.anim_node.as_ref().map(AnimNode::as_str)),
+            ("Sound", self.sound.as_deref()),
+            ("Prop1", self.prop1.as_deref()),
+            ("Prop2", self.prop2.as_deref()),
+        ];
+        for (name, value) in metadata {
+            if let Some(value) = value {
+                writeln!(f, "    {}:{},", name, value)?;
+            }
+        }
+        let toggles = [
+            ("RemoveResultItem", self.remove_result_item),
+            ("AllowFrozenItem", self.allow_frozen_item),
+            ("InSameInventory", self.in_same_inventory),
+            ("Obsolete", self.obsolete),
+            ("StopOnWalk", self.stop_on_walk),
+            ("StopOnRun", self.stop_on_run),
+        ];
+        for (name, value) in toggles {
+            if let Some(value) = value {
+                writeln!(f, "    {}:{},", name, value)?;
+            }
+        }
+        let heats = [("Heat", self.heat), ("CookingHeat", self.cooking_heat)];
+        for (name, value) in heats {
+            if let Some(value) = value {
+                writeln!(f, "    {}:{},", name, value)?;
+            }
+        }
+        for (name, value) in &self.extra {
+            writeln!(f, "    {}:{},", name, value)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+impl Named for Recipe {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// A recipe compared, ordered and hashed by its name alone, for use as a
+/// key in sets and maps. Two recipes with the same name are equal here even
+/// if their bodies differ.
+#[derive(Debug)]
+pub struct RecipeByName(pub Recipe);
+
+impl PartialEq for RecipeByName {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.name == other.0.name
+    }
+}
+
+impl Eq for RecipeByName {}
+
+impl PartialOrd for RecipeByName {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RecipeByName {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.name.cmp(&other.0.name)
+    }
+}
+
+impl Hash for RecipeByName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.name.hash(state);
     }
 }
 
@@ -56,42 +642,329 @@ impl<'a> From<(&'a str, RecipeBody<'a>)> for Recipe {
             time,
             category,
             need_to_be_learned,
+            skills_required,
+            needs,
+            tags,
+            on_create,
+            on_test,
+            anim_node,
+            sound,
+            prop1,
+            prop2,
+            remove_result_item,
+            allow_frozen_item,
+            in_same_inventory,
+            obsolete,
+            stop_on_walk,
+            stop_on_run,
+            heat,
+            cooking_heat,
+            extra,
         } = body;
         Recipe {
-            name: name.to_string(),
-            ingredients: ingredients.into_iter().map(|s| s.to_string()).collect(),
-            result: result.to_string(),
+            name: normalize_name(name),
+            ingredients: ingredients.into_iter().collect(),
+            result,
             time,
-            category: category.to_string(),
+            category: RecipeCategory::from(category),
             need_to_be_learned,
+            skills_required,
+            needs,
+            tags: tags.into_iter().map(str::to_string).collect(),
+            on_create: on_create.map(str::to_string),
+            on_test: on_test.map(str::to_string),
+            anim_node: anim_node.map(AnimNode::from),
+            sound: sound.map(str::to_string),
+            prop1: prop1.map(str::to_string),
+            prop2: prop2.map(str::to_string),
+            remove_result_item,
+            allow_frozen_item,
+            in_same_inventory,
+            obsolete,
+            stop_on_walk,
+            stop_on_run,
+            heat,
+            cooking_heat,
+            extra: extra
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            leading_comments: Vec::new(),
+            module: None,
         }
     }
 }
 
-fn recipe_ingredient<'a, E>(input: &'a str) -> IResult<&'a str, &'a str, E>
+/// The ingredient grammar `recipe` uses: counts written either way round,
+/// plus the tag, `mapper:` and `fluid:` forms.
+pub fn default_ingredient<'a, E>(input: &'a str) -> IResult<&'a str, Ingredient, E>
+where
+    E: ParseError<&'a str>,
+{
+    alt((leading_count_ingredient, ingredient))(input)
+}
+
+/// Whether `time` can be a recipe's `Time`: finite and not negative.
+pub(crate) fn is_valid_time(time: f32) -> bool {
+    time.is_finite() && time >= 0.0
+}
+
+/// An ingredient line, as parsed by `ingredient`. The comma is optional
+/// when the ingredient is the last thing on its line, since hand-edited
+/// files often leave it off.
+fn recipe_ingredient<'a, E, P>(
+    mut ingredient: P,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Ingredient, E>
+where
+    E: ParseError<&'a str>,
+    P: Parser<&'a str, Ingredient, E>,
+{
+    move |input: &'a str| terminated(|i| ingredient.parse(i), alt((tag(","), line_end)))(input)
+}
+
+fn unknown_field<'a, E>(
+    options: ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, RecipeField<'a>, E>
 where
     E: ParseError<&'a str>,
 {
-    terminated(identifier1, tag(","))(input)
+    move |input: &'a str| {
+        if !options.collect_unknown_fields {
+            return Err(nom::Err::Error(E::from_error_kind(input, ErrorKind::Tag)));
+        }
+        let (input, name) = preceded(space0, alphanumeric1)(input)?;
+        let (input, _) = delimited(space0, tag(":"), space0)(input)?;
+        let (input, value) = map(opt(text_value_with(options)), Option::unwrap_or_default)(input)?;
+        let (input, _) = field_terminator(options)(input)?;
+        Ok((input, RecipeField::Unknown(name, value)))
+    }
 }
 
-fn recipe_body<'a, E>(input: &'a str) -> IResult<&'a str, RecipeBody, E>
+/// The header fields `recipe_field` understands, for tools that check
+/// field names without running the parser.
+pub(crate) const FIELDS: &[&str] = &[
+    "Result",
+    "Time",
+    "Category",
+    "NeedToBeLearn",
+    "SkillRequired",
+    "Needs",
+    "Tags",
+    "OnCreate",
+    "OnTest",
+    "AnimNode",
+    "Sound",
+    "Prop1",
+    "Prop2",
+    "RemoveResultItem",
+    "AllowFrozenItem",
+    "InSameInventory",
+    "Obsolete",
+    "StopOnWalk",
+    "StopOnRun",
+    "Heat",
+    "CookingHeat",
+];
+
+fn recipe_field<'a, E>(
+    options: ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, RecipeField<'a>, E>
 where
     E: ParseError<&'a str>,
 {
-    let (input, ingredients) = separated_list1(multispace1, recipe_ingredient)(input)?;
-    let (input, _) = multispace1(input)?;
+    move |input: &'a str| {
+        let terminator = || field_terminator(options);
+        let text_field = |name: &'static str| {
+            field_value_terminated(name, ":", text_value_with(options), terminator())
+        };
+        let bool_field = |name: &'static str| {
+            field_value_terminated(name, ":", bool_value_with(options), terminator())
+        };
+        alt((
+            map(
+                field_value_terminated("Result", ":", result_spec, terminator()),
+                RecipeField::Result,
+            ),
+            map(
+                field_value_terminated("Time", ":", time_value, terminator()),
+                RecipeField::Time,
+            ),
+            map(
+                field_value_terminated("Category", ":", text_value_with(options), terminator()),
+                RecipeField::Category,
+            ),
+            map(
+                field_value_terminated(
+                    "NeedToBeLearn",
+                    ":",
+                    bool_value_with(options),
+                    terminator(),
+                ),
+                RecipeField::NeedToBeLearn,
+            ),
+            map(
+                field_value_terminated(
+                    "SkillRequired",
+                    ":",
+                    list_value(skill_requirement),
+                    terminator(),
+                ),
+                RecipeField::SkillRequired,
+            ),
+            map(
+                field_value_terminated("Needs", ":", list_value(need), terminator()),
+                RecipeField::Needs,
+            ),
+            map(
+                field_value_terminated("Tags", ":", list_value(identifier1), terminator()),
+                RecipeField::Tags,
+            ),
+            map(text_field("OnCreate"), RecipeField::OnCreate),
+            map(text_field("OnTest"), RecipeField::OnTest),
+            map(text_field("AnimNode"), RecipeField::AnimNode),
+            map(text_field("Sound"), RecipeField::Sound),
+            map(text_field("Prop1"), RecipeField::Prop1),
+            map(text_field("Prop2"), RecipeField::Prop2),
+            // Grouped to stay within the number of choices `alt` takes.
+            alt((
+                map(
+                    bool_field("RemoveResultItem"),
+                    RecipeField::RemoveResultItem,
+                ),
+                map(bool_field("AllowFrozenItem"), RecipeField::AllowFrozenItem),
+                map(bool_field("InSameInventory"), RecipeField::InSameInventory),
+                map(bool_field("Obsolete"), RecipeField::Obsolete),
+                map(bool_field("StopOnWalk"), RecipeField::StopOnWalk),
+                map(bool_field("StopOnRun"), RecipeField::StopOnRun),
+            )),
+            map(
+                field_value_terminated("Heat", ":", float, terminator()),
+                RecipeField::Heat,
+            ),
+            map(
+                field_value_terminated("CookingHeat", ":", float, terminator()),
+                RecipeField::CookingHeat,
+            ),
+            unknown_field(options),
+        ))(input)
+    }
+}
 
-    let (input, result) = field_value("Result", ":", alphanumeric1)(input)?;
-    let (input, _) = multispace1(input)?;
+fn recipe_body<'a, E, P>(
+    options: ParseOptions,
+    ingredient: P,
+) -> impl FnMut(&'a str) -> IResult<&'a str, RecipeBody<'a>, E>
+where
+    E: ParseError<&'a str> + ContextError<&'a str>,
+    P: Parser<&'a str, Ingredient, E>,
+{
+    let mut ingredient = recipe_ingredient(ingredient);
+    move |input: &'a str| {
+        let (input, _) = leading_comments(options)(input)?;
+        // Every entry ends with its own comma or line break, so no further
+        // whitespace is needed between them and a whole recipe may be
+        // written on one line.
+        let (input, ingredients) = many0(preceded(whitespace0(options), &mut ingredient))(input)?;
+        let (input, fields) = many0(preceded(whitespace0(options), recipe_field(options)))(input)?;
+        let (input, _) = trailing_comments(options)(input)?;
+        collect_fields(input, ingredients, None, fields)
+    }
+}
 
-    let (input, time) = field_value("Time", ":", float)(input)?;
-    let (input, _) = multispace1(input)?;
+/// The newer layout, where the ingredients and the result sit in their own
+/// `inputs` and `outputs` sub-blocks ahead of the other fields.
+fn recipe_v2_body<'a, E>(
+    options: ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, RecipeBody<'a>, E>
+where
+    E: ParseError<&'a str> + ContextError<&'a str>,
+{
+    move |input: &'a str| {
+        let (input, _) = leading_comments(options)(input)?;
+        let (input, ingredients) =
+            unnamed_block_repeated("inputs", recipe_ingredient(default_ingredient))(input)?;
+        let (input, _) = separator(options)(input)?;
+        let (input, result) =
+            unnamed_block("outputs", terminated(result_spec, opt(tag(","))))(input)?;
+        let (input, fields) = many0(preceded(separator(options), recipe_field(options)))(input)?;
+        let (input, _) = trailing_comments(options)(input)?;
+        collect_fields(input, ingredients, Some(result), fields)
+    }
+}
 
-    let (input, category) = field_value("Category", ":", alphanumeric1)(input)?;
-    let (input, _) = multispace1(input)?;
+/// Gather parsed fields into a body, checking that the required ones are
+/// present. `input` is where the field list stopped, for error reporting.
+fn collect_fields<'a, E>(
+    input: &'a str,
+    ingredients: Vec<Ingredient>,
+    mut result: Option<ResultSpec>,
+    fields: Vec<RecipeField<'a>>,
+) -> IResult<&'a str, RecipeBody<'a>, E>
+where
+    E: ParseError<&'a str> + ContextError<&'a str>,
+{
+    let mut time = None;
+    let mut category = None;
+    let mut need_to_be_learned = None;
+    let mut skills_required = Vec::new();
+    let mut needs = Vec::new();
+    let mut tags = Vec::new();
+    let mut on_create = None;
+    let mut on_test = None;
+    let mut anim_node = None;
+    let mut sound = None;
+    let mut prop1 = None;
+    let mut prop2 = None;
+    let mut remove_result_item = None;
+    let mut allow_frozen_item = None;
+    let mut in_same_inventory = None;
+    let mut obsolete = None;
+    let mut stop_on_walk = None;
+    let mut stop_on_run = None;
+    let mut heat = None;
+    let mut cooking_heat = None;
+    let mut extra = Vec::new();
+    for field in fields {
+        match field {
+            RecipeField::Result(value) => result = Some(value),
+            RecipeField::Time(value) => time = Some(value),
+            RecipeField::Category(value) => category = Some(value),
+            RecipeField::NeedToBeLearn(value) => need_to_be_learned = Some(value),
+            RecipeField::SkillRequired(value) => skills_required = value,
+            RecipeField::Needs(value) => needs = value,
+            RecipeField::Tags(value) => tags = value,
+            RecipeField::OnCreate(value) => on_create = Some(value),
+            RecipeField::OnTest(value) => on_test = Some(value),
+            RecipeField::AnimNode(value) => anim_node = Some(value),
+            RecipeField::Sound(value) => sound = Some(value),
+            RecipeField::Prop1(value) => prop1 = Some(value),
+            RecipeField::Prop2(value) => prop2 = Some(value),
+            RecipeField::RemoveResultItem(value) => remove_result_item = Some(value),
+            RecipeField::AllowFrozenItem(value) => allow_frozen_item = Some(value),
+            RecipeField::InSameInventory(value) => in_same_inventory = Some(value),
+            RecipeField::Obsolete(value) => obsolete = Some(value),
+            RecipeField::StopOnWalk(value) => stop_on_walk = Some(value),
+            RecipeField::StopOnRun(value) => stop_on_run = Some(value),
+            RecipeField::Heat(value) => heat = Some(value),
+            RecipeField::CookingHeat(value) => cooking_heat = Some(value),
+            RecipeField::Unknown(name, value) => extra.push((name, value)),
+        }
+    }
 
-    let (input, need_to_be_learned) = field_value("NeedToBeLearn", ":", bool_value)(input)?;
+    // A required field that never matched is reported at the point the
+    // field list stopped, which is usually the misspelled field.
+    let missing = |message: &'static str| {
+        nom::Err::Error(E::add_context(
+            input,
+            message,
+            E::from_error_kind(input, ErrorKind::Tag),
+        ))
+    };
+    let result = result.ok_or_else(|| missing("expected field 'Result'"))?;
+    let time = time.ok_or_else(|| missing("expected field 'Time'"))?;
+    let category = category.ok_or_else(|| missing("expected field 'Category'"))?;
+    // Recipes that anyone can craft usually leave `NeedToBeLearn` out.
+    let need_to_be_learned = need_to_be_learned.unwrap_or(false);
 
     Ok((
         input,
@@ -101,20 +974,159 @@ where
             time,
             category,
             need_to_be_learned,
+            skills_required,
+            needs,
+            tags,
+            on_create,
+            on_test,
+            anim_node,
+            sound,
+            prop1,
+            prop2,
+            remove_result_item,
+            allow_frozen_item,
+            in_same_inventory,
+            obsolete,
+            stop_on_walk,
+            stop_on_run,
+            heat,
+            cooking_heat,
+            extra,
         },
     ))
 }
 
+/// Parse a recipe block. Empty or whitespace-only input is reported with
+/// an "empty input" context rather than a bare tag mismatch.
 pub fn recipe<'a, E>(input: &'a str) -> IResult<&'a str, Recipe, E>
 where
-    E: ParseError<&'a str>,
+    E: ParseError<&'a str> + ContextError<&'a str>,
+{
+    if input.trim().is_empty() {
+        return Err(nom::Err::Error(E::add_context(
+            input,
+            "empty input",
+            E::from_error_kind(input, ErrorKind::Eof),
+        )));
+    }
+    recipe_with_options(ParseOptions::default())(input)
+}
+
+pub fn recipe_with_options<'a, E>(
+    options: ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Recipe, E>
+where
+    E: ParseError<&'a str> + ContextError<&'a str>,
+{
+    recipe_with_ingredient_parser(options, default_ingredient)
+}
+
+/// `recipe_with_options`, with `ingredient` in place of the default
+/// ingredient grammar. The parser sees one ingredient at a time; the comma
+/// or line break after it is handled here.
+pub fn recipe_with_ingredient_parser<'a, E, P>(
+    options: ParseOptions,
+    ingredient: P,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Recipe, E>
+where
+    E: ParseError<&'a str> + ContextError<&'a str>,
+    P: Parser<&'a str, Ingredient, E>,
+{
+    let mut parser = Parser::into(named_block_with_options(
+        "recipe",
+        options,
+        recipe_body(options, ingredient),
+    ));
+    move |input: &'a str| parser.parse(input)
+}
+
+/// Parse a recipe written in the newer sub-block style:
+///
+/// ```text
+/// recipe Make Mildew Cure
+/// {
+///     inputs
+///     {
+///         GardeningSprayEmpty,
+///         Base.Milk,
+///     }
+///     outputs
+///     {
+///         GardeningSprayMilk,
+///     }
+///     Time:40.0,
+///     Category:Farming,
+/// }
+/// ```
+///
+/// The result is the same `Recipe` that `recipe` produces for the flat form.
+pub fn recipe_v2<'a, E>(input: &'a str) -> IResult<&'a str, Recipe, E>
+where
+    E: ParseError<&'a str> + ContextError<&'a str>,
+{
+    let options = ParseOptions::default();
+    Parser::into(named_block_with_options(
+        "recipe",
+        options,
+        recipe_v2_body(options),
+    ))
+    .parse(input)
+}
+
+/// `recipe` as a value, so that nom's `Parser` methods such as `map` and
+/// `and` can be called on it directly.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RecipeParser;
+
+impl<'a, E> Parser<&'a str, Recipe, E> for RecipeParser
+where
+    E: ParseError<&'a str> + ContextError<&'a str>,
+{
+    fn parse(&mut self, input: &'a str) -> IResult<&'a str, Recipe, E> {
+        recipe(input)
+    }
+}
+
+/// Parse a recipe that may have been cut off before its closing brace,
+/// returning it with whether the block was actually closed. The last field
+/// may run up to the end of input without a comma.
+pub fn recipe_partial<'a, E>(input: &'a str) -> IResult<&'a str, (Recipe, bool), E>
+where
+    E: ParseError<&'a str> + ContextError<&'a str>,
 {
-    Parser::into(named_block("recipe", recipe_body)).parse(input)
+    let options = ParseOptions {
+        allow_trailing_comma: true,
+        ..ParseOptions::default()
+    };
+
+    let (input, _) = pair(tag("recipe"), space1)(input)?;
+    let (input, name) = string_with_spaces_delimited_by_open_brace(input)?;
+    let (input, _) = tuple((multispace0, tag("{"), multispace1))(input)?;
+    let (input, body) = recipe_body(options, default_ingredient)(input)?;
+    let (input, complete) = alt((
+        value(true, pair(multispace1, tag("}"))),
+        value(false, pair(multispace0, eof)),
+    ))(input)?;
+    Ok((input, (Recipe::from((name, body)), complete)))
+}
+
+/// Parse a single recipe from a string, with errors that record which
+/// required field could not be found.
+impl<'a> TryFrom<&'a str> for Recipe {
+    type Error = nom::Err<VerboseError<&'a str>>;
+
+    fn try_from(input: &'a str) -> Result<Self, Self::Error> {
+        let (_, recipe) = all_consuming(recipe)(input.trim())?;
+        Ok(recipe)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use nom::sequence::preceded;
+    use std::collections::BTreeSet;
+
+    use nom::character::complete::multispace1;
+    use proptest::prelude::*;
 
     use super::*;
 
@@ -148,4 +1160,1340 @@ recipe Make Mildew Cure
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn parse_recipe_with_custom_ingredient_parser() {
+        let bare = |input| map(identifier1, |name| Ingredient::new(name, 1))(input);
+        let recipe_text = "recipe Make Mildew Cure
+{
+    GardeningSprayEmpty,
+    Base.Milk,
+
+    Result:GardeningSprayMilk,
+    Time:40.0,
+    Category:Farming,
+}";
+        let expected = Recipe::new(
+            "Make Mildew Cure",
+            vec!["GardeningSprayEmpty", "Base.Milk"],
+            "GardeningSprayMilk",
+            40.0,
+            "Farming",
+            false,
+        );
+
+        let recipe_res: Result<Recipe> =
+            recipe_with_ingredient_parser(ParseOptions::default(), bare)(recipe_text);
+        let (_, actual) = recipe_res.expect("failed to parse recipe");
+        assert_eq!(expected, actual);
+
+        let counted_text = "recipe Make Mildew Cure
+{
+    Nails=2,
+
+    Result:GardeningSprayMilk,
+    Time:40.0,
+    Category:Farming,
+}";
+        let counted_res: Result<Recipe> =
+            recipe_with_ingredient_parser(ParseOptions::default(), bare)(counted_text);
+        assert!(counted_res.is_err());
+    }
+
+    #[cfg(feature = "smallvec")]
+    #[test]
+    fn few_ingredients_are_stored_inline() {
+        let recipe = Recipe::new(
+            "Make Mildew Cure",
+            vec!["GardeningSprayEmpty", "Base.Milk", "Nails", "Plank"],
+            "GardeningSprayMilk",
+            40.0,
+            "Farming",
+            true,
+        );
+
+        assert!(!recipe.ingredients.spilled());
+    }
+
+    #[test]
+    fn merge_duplicate_ingredients() {
+        let module_text = "
+recipe Make Crate
+{
+  Nails=2,
+  Plank,
+  Nails=3,
+
+  Result:WoodenCrate,
+  Time:80.0,
+  Category:Carpentry,
+  NeedToBeLearn:false,
+}
+";
+        let module_res: Result<Recipe> = preceded(multispace1, recipe)(module_text);
+        let (_, actual) = module_res.expect("failed to parse module");
+
+        let merged = actual.with_merged_ingredients();
+
+        assert_eq!(
+            &[Ingredient::new("Nails", 5), Ingredient::new("Plank", 1)],
+            merged.ingredients()
+        );
+    }
+
+    #[test]
+    fn parse_skill_required() {
+        let module_text = "
+recipe Make Cake Batter
+{
+  Flour,
+  Egg,
+
+  Result:CakeBatter,
+  Time:50.0,
+  Category:Cooking,
+  NeedToBeLearn:false,
+  SkillRequired:Cooking:3;Farming:2,
+}
+";
+        let module_res: Result<Recipe> = preceded(multispace1, recipe)(module_text);
+        let (_, actual) = module_res.expect("failed to parse module");
+
+        assert_eq!(
+            &[SkillReq::new("Cooking", 3), SkillReq::new("Farming", 2)],
+            actual.skills_required()
+        );
+    }
+
+    #[test]
+    fn parse_needs() {
+        let recipe_text = "recipe Make Cake Batter
+{
+  Flour,
+
+  Result:CakeBatter,
+  Time:50.0,
+  Category:Cooking,
+  Needs:Cooking:2;Base.KitchenKnife,
+}";
+
+        let recipe_res: Result<Recipe> = recipe(recipe_text);
+        let (_, actual) = recipe_res.expect("failed to parse recipe");
+
+        assert_eq!(
+            &[
+                Need::Skill(SkillReq::new("Cooking", 2)),
+                Need::Item(String::from("Base.KitchenKnife")),
+            ],
+            actual.needs()
+        );
+        assert!(actual
+            .to_string()
+            .contains("    Needs:Cooking:2;Base.KitchenKnife,\n"));
+    }
+
+    #[test]
+    fn parse_tab_indented_recipe() {
+        let module_text = "
+recipe Make\tMildew Cure\t
+{
+\tGardeningSprayEmpty,
+\tBase.Milk,
+
+\tResult:GardeningSprayMilk,
+\tTime:40.0,
+\tCategory:Farming,
+\tNeedToBeLearn:true,
+}
+";
+        let expected = Recipe::new(
+            "Make Mildew Cure",
+            vec!["GardeningSprayEmpty", "Base.Milk"],
+            "GardeningSprayMilk",
+            40.0,
+            "Farming",
+            true,
+        );
+
+        let module_res: Result<Recipe> = preceded(multispace1, recipe)(module_text);
+        let (_, actual) = module_res.expect("failed to parse module");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn parse_tab_aligned_fields() {
+        let recipe_text = "recipe Make Mildew Cure
+{
+    GardeningSprayEmpty,
+
+    Result\t\t:\tGardeningSprayMilk,
+    Time\t\t\t:\t40.0,
+    Category\t:\tFarming,
+    NeedToBeLearn\t:\ttrue,
+}";
+        let expected = Recipe::new(
+            "Make Mildew Cure",
+            vec!["GardeningSprayEmpty"],
+            "GardeningSprayMilk",
+            40.0,
+            "Farming",
+            true,
+        );
+
+        let recipe_res: Result<Recipe> = recipe(recipe_text);
+        let (_, actual) = recipe_res.expect("failed to parse recipe");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn qualified_ingredient_names() {
+        let recipe = Recipe::new(
+            "Make Mildew Cure",
+            vec!["GardeningSprayEmpty", "Base.Milk"],
+            "GardeningSprayMilk",
+            40.0,
+            "Farming",
+            true,
+        );
+
+        let actual: Vec<_> = recipe.qualified_ingredients().collect();
+
+        assert_eq!(
+            vec![(None, "GardeningSprayEmpty"), (Some("Base"), "Milk")],
+            actual
+        );
+    }
+
+    #[test]
+    fn craftable_only_when_all_ingredients_are_available() {
+        let recipe = Recipe::new(
+            "Make Mildew Cure",
+            vec!["GardeningSprayEmpty", "Base.Milk"],
+            "GardeningSprayMilk",
+            40.0,
+            "Farming",
+            true,
+        );
+        let mut available = HashSet::from([String::from("GardeningSprayEmpty")]);
+
+        assert!(!recipe.is_craftable_from(&available));
+
+        available.insert(String::from("Base.Milk"));
+        assert!(recipe.is_craftable_from(&available));
+    }
+
+    #[test]
+    fn ingredient_counts_sum_duplicates() {
+        let recipe = Recipe::new(
+            "Make Mildew Cure",
+            vec![
+                Ingredient::new("Base.Milk", 2),
+                Ingredient::new("GardeningSprayEmpty", 1),
+                Ingredient::new("Base.Milk", 3),
+            ],
+            "GardeningSprayMilk",
+            40.0,
+            "Farming",
+            true,
+        );
+
+        let actual = recipe.ingredients_as_counts();
+
+        assert_eq!(2, actual.len());
+        assert_eq!(5, actual["Base.Milk"]);
+        assert_eq!(1, actual["GardeningSprayEmpty"]);
+    }
+
+    #[test]
+    fn ingredient_counts_saturate() {
+        let recipe = Recipe::new(
+            "Make Mildew Cure",
+            vec![
+                Ingredient::new("Base.Milk", u32::MAX),
+                Ingredient::new("Base.Milk", 2),
+            ],
+            "GardeningSprayMilk",
+            40.0,
+            "Farming",
+            true,
+        );
+
+        assert_eq!(u32::MAX, recipe.ingredients_as_counts()["Base.Milk"]);
+    }
+
+    #[test]
+    fn parse_single_line_recipe() {
+        let recipe_text = "recipe Make Table { Nails=2,Planks=4,Result:Table,Time:60,Category:Carpentry,NeedToBeLearn:true, }";
+        let expected = Recipe::new(
+            "Make Table",
+            vec![Ingredient::new("Nails", 2), Ingredient::new("Planks", 4)],
+            "Table",
+            60.0,
+            "Carpentry",
+            true,
+        );
+
+        let recipe_res: Result<Recipe> = recipe(recipe_text);
+        let (rest, actual) = recipe_res.expect("failed to parse recipe");
+
+        assert_eq!("", rest);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn default_recipe_is_empty_and_invalid() {
+        let recipe = Recipe::default();
+
+        assert_eq!("", recipe.name());
+        assert!(recipe.ingredients().is_empty());
+        assert_eq!("", recipe.result().item);
+        assert_eq!(0.0, recipe.time());
+        assert_eq!("", recipe.category().as_str());
+        assert!(!recipe.need_to_be_learned());
+        assert_eq!(Err(RecipeError::EmptyName), recipe.validate());
+    }
+
+    #[test]
+    fn display_name_splits_camel_case() {
+        let bandage = Recipe::new(
+            "MakeBandage",
+            vec!["RippedSheets"],
+            "Bandage",
+            5.0,
+            "Health",
+            false,
+        );
+        let mildew_cure = Recipe::new(
+            "Make Mildew Cure",
+            vec!["GardeningSprayEmpty", "Base.Milk"],
+            "GardeningSprayMilk",
+            40.0,
+            "Farming",
+            true,
+        );
+
+        assert_eq!("Make Bandage", bandage.display_name());
+        assert_eq!("Make Mildew Cure", mildew_cure.display_name());
+    }
+
+    #[test]
+    fn parse_brace_touching_name() {
+        let recipe_text = "recipe Foo{
+  Plank,
+  Result:Bar,
+  Time:10.0,
+  Category:Carpentry,
+}";
+
+        let recipe_res: Result<Recipe> = recipe(recipe_text);
+        let (_, actual) = recipe_res.expect("failed to parse recipe");
+
+        assert_eq!("Foo", actual.name());
+        assert_eq!("Bar", actual.result().item);
+    }
+
+    #[test]
+    fn parse_module_qualified_category() {
+        let recipe_text = "recipe Make Mildew Cure
+{
+  GardeningSprayEmpty,
+
+  Result:GardeningSprayMilk,
+  Time:40.0,
+  Category:Base.Farming,
+}";
+
+        let recipe_res: Result<Recipe> = recipe(recipe_text);
+        let (_, actual) = recipe_res.expect("failed to parse recipe");
+
+        assert_eq!(
+            &RecipeCategory::Other(String::from("Base.Farming")),
+            actual.category()
+        );
+    }
+
+    #[test]
+    fn parse_category_with_spaces() {
+        let module_text = "
+recipe Make Cake Batter
+{
+  Flour,
+
+  Result:CakeBatter,
+  Time:50.0,
+  Category:Cooking Skill,
+  NeedToBeLearn:false,
+}
+";
+        let expected = Recipe::new(
+            "Make Cake Batter",
+            vec!["Flour"],
+            "CakeBatter",
+            50.0,
+            "Cooking Skill",
+            false,
+        );
+
+        let module_res: Result<Recipe> = preceded(multispace1, recipe)(module_text);
+        let (_, actual) = module_res.expect("failed to parse module");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn missing_field_comma_requires_option() {
+        let recipe_text = "recipe Make Cake Batter
+{
+  Flour,
+
+  Result:CakeBatter
+  Time:50.0,
+  Category:Cooking,
+  NeedToBeLearn:false
+}";
+        let expected = Recipe::new(
+            "Make Cake Batter",
+            vec!["Flour"],
+            "CakeBatter",
+            50.0,
+            "Cooking",
+            false,
+        );
+
+        let strict_res: Result<Recipe> = recipe(recipe_text);
+        assert!(strict_res.is_err());
+
+        let options = ParseOptions {
+            allow_trailing_comma: true,
+            ..ParseOptions::default()
+        };
+        let lenient_res: Result<Recipe> = recipe_with_options(options)(recipe_text);
+        let (_, actual) = lenient_res.expect("failed to parse recipe");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn unknown_fields_require_option() {
+        let recipe_text = "recipe Make Cake Batter
+{
+  Flour,
+
+  Result:CakeBatter,
+  Time:50.0,
+  Category:Cooking,
+  OnGiveXP:Give10CookingXP,
+  NeedToBeLearn:false,
+}";
+
+        let strict_res: Result<Recipe> = recipe(recipe_text);
+        assert!(strict_res.is_err());
+
+        let options = ParseOptions {
+            collect_unknown_fields: true,
+            ..ParseOptions::default()
+        };
+        let lenient_res: Result<Recipe> = recipe_with_options(options)(recipe_text);
+        let (_, actual) = lenient_res.expect("failed to parse recipe");
+
+        assert_eq!(
+            &[(String::from("OnGiveXP"), String::from("Give10CookingXP"))],
+            actual.extra()
+        );
+    }
+
+    #[test]
+    fn typed_extra_fields() {
+        let recipe_text = "recipe Make Cake Batter
+{
+  Flour,
+
+  Result:CakeBatter,
+  Time:50.0,
+  Category:Cooking,
+  Weight:0.5,
+  IsHidden:true,
+}";
+        let options = ParseOptions {
+            collect_unknown_fields: true,
+            ..ParseOptions::default()
+        };
+        let recipe_res: Result<Recipe> = recipe_with_options(options)(recipe_text);
+        let (_, actual) = recipe_res.expect("failed to parse recipe");
+
+        assert_eq!(Some(0.5), actual.extra_f32("Weight"));
+        assert_eq!(Some(true), actual.extra_bool("IsHidden"));
+        assert_eq!(None, actual.extra_bool("Weight"));
+        assert_eq!(None, actual.extra_f32("Missing"));
+    }
+
+    #[test]
+    fn numeric_bools_require_option() {
+        let recipe_text = "recipe Make Cake Batter
+{
+  Flour,
+
+  Result:CakeBatter,
+  Time:50.0,
+  Category:Cooking,
+  NeedToBeLearn:1,
+}";
+        let expected = Recipe::new(
+            "Make Cake Batter",
+            vec!["Flour"],
+            "CakeBatter",
+            50.0,
+            "Cooking",
+            true,
+        );
+
+        let strict_res: Result<Recipe> = recipe(recipe_text);
+        assert!(strict_res.is_err());
+
+        let options = ParseOptions {
+            lenient_bools: true,
+            ..ParseOptions::default()
+        };
+        let lenient_res: Result<Recipe> = recipe_with_options(options)(recipe_text);
+        let (_, actual) = lenient_res.expect("failed to parse recipe");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn comments_require_option() {
+        let recipe_text = "recipe Make Cake Batter
+{
+  /* the only ingredient */
+  Flour,
+  // header fields
+  Result:CakeBatter,
+  Time:50.0,
+  Category:Cooking,
+  NeedToBeLearn:false,
+  /* done */
+}";
+        let expected = Recipe::new(
+            "Make Cake Batter",
+            vec!["Flour"],
+            "CakeBatter",
+            50.0,
+            "Cooking",
+            false,
+        );
+
+        let strict_res: Result<Recipe> = recipe(recipe_text);
+        assert!(strict_res.is_err());
+
+        let options = ParseOptions {
+            allow_comments: true,
+            ..ParseOptions::default()
+        };
+        let lenient_res: Result<Recipe> = recipe_with_options(options)(recipe_text);
+        let (_, actual) = lenient_res.expect("failed to parse recipe");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn comment_between_tag_and_name() {
+        let recipe_text = "recipe /* legacy */ Make Cake Batter {
+  Flour,
+
+  Result:CakeBatter,
+  Time:50.0,
+  Category:Cooking,
+}";
+        let expected = Recipe::new(
+            "Make Cake Batter",
+            vec!["Flour"],
+            "CakeBatter",
+            50.0,
+            "Cooking",
+            false,
+        );
+
+        let options = ParseOptions {
+            allow_comments: true,
+            ..ParseOptions::default()
+        };
+        let lenient_res: Result<Recipe> = recipe_with_options(options)(recipe_text);
+        let (_, actual) = lenient_res.expect("failed to parse recipe");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn recipe_parser_composes_with_parser_methods() {
+        let module_text = "
+recipe Make Cake Batter
+{
+  Flour,
+
+  Result:CakeBatter,
+  Time:50.0,
+  Category:Cooking,
+}
+";
+
+        // The error type is a parameter of the `Parser` trait rather than of
+        // `RecipeParser`, so it has to be named when calling its methods.
+        let time = Parser::<_, _, nom::error::Error<&str>>::map(RecipeParser, |r| r.time());
+        let time_res: Result<f32> = preceded(multispace1, time)(module_text);
+        let (_, actual) = time_res.expect("failed to parse recipe");
+
+        assert_eq!(50.0, actual);
+    }
+
+    #[test]
+    fn recipes_ordered_by_name() {
+        let mildew_cure = Recipe::new(
+            "Make Mildew Cure",
+            vec!["GardeningSprayEmpty", "Base.Milk"],
+            "GardeningSprayMilk",
+            40.0,
+            "Farming",
+            true,
+        );
+        let cake_batter = Recipe::new(
+            "Make Cake Batter",
+            vec!["Flour"],
+            "CakeBatter",
+            50.0,
+            "Cooking",
+            false,
+        );
+        let slower_mildew_cure = Recipe::new(
+            "Make Mildew Cure",
+            vec!["GardeningSprayEmpty", "Base.Milk"],
+            "GardeningSprayMilk",
+            80.0,
+            "Farming",
+            true,
+        );
+
+        let mut recipes = BTreeSet::new();
+        assert!(recipes.insert(RecipeByName(mildew_cure)));
+        assert!(recipes.insert(RecipeByName(cake_batter)));
+        assert!(!recipes.insert(RecipeByName(slower_mildew_cure)));
+
+        let names: Vec<&str> = recipes.iter().map(|r| r.0.name()).collect();
+        assert_eq!(vec!["Make Cake Batter", "Make Mildew Cure"], names);
+    }
+
+    #[test]
+    fn rebuild_recipe_from_parts() {
+        let expected = Recipe::new(
+            "Make Mildew Cure",
+            vec!["GardeningSprayEmpty", "Base.Milk"],
+            "GardeningSprayMilk",
+            40.0,
+            "Farming",
+            true,
+        );
+        let original = Recipe::new(
+            "Make Mildew Cure",
+            vec!["GardeningSprayEmpty", "Base.Milk"],
+            "GardeningSprayMilk",
+            40.0,
+            "Farming",
+            true,
+        );
+
+        let (name, ingredients, result, time, category, need_to_be_learned) = original.into_parts();
+        let actual = Recipe::new(
+            name,
+            ingredients,
+            result,
+            time,
+            category,
+            need_to_be_learned,
+        );
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn drain_ingredients_from_owned_recipe() {
+        let recipe = Recipe::new(
+            "Make Mildew Cure",
+            vec!["GardeningSprayEmpty", "Base.Milk"],
+            "GardeningSprayMilk",
+            40.0,
+            "Farming",
+            true,
+        );
+
+        let names: Vec<String> = recipe
+            .into_ingredients()
+            .into_iter()
+            .map(|ingredient| match ingredient {
+                Ingredient::Item { name, .. } => name,
+                other => panic!("expected an item, got {other:?}"),
+            })
+            .collect();
+
+        assert_eq!(
+            vec![
+                String::from("GardeningSprayEmpty"),
+                String::from("Base.Milk")
+            ],
+            names
+        );
+    }
+
+    #[test]
+    fn total_time_scales_with_quantity() {
+        let recipe = Recipe::new(
+            "Make Mildew Cure",
+            vec!["GardeningSprayEmpty", "Base.Milk"],
+            "GardeningSprayMilk",
+            40.0,
+            "Farming",
+            true,
+        );
+
+        assert_eq!(200.0, recipe.total_time_for(5));
+    }
+
+    #[test]
+    fn edit_recipe_and_round_trip() {
+        let mut edited = Recipe::new(
+            "Make Mildew Cure",
+            vec!["GardeningSprayEmpty", "Base.Milk"],
+            "GardeningSprayMilk",
+            40.0,
+            "Farming",
+            true,
+        );
+
+        assert_eq!(Err(RecipeError::EmptyName), edited.set_name("  "));
+        assert_eq!(Err(RecipeError::EmptyResult), edited.set_result(""));
+        assert_eq!(Err(RecipeError::InvalidTime), edited.set_time(-1.0));
+
+        edited.set_name("Make Strong Mildew Cure").unwrap();
+        edited.set_result("GardeningSprayCigarettes").unwrap();
+        edited.set_time(60.5).unwrap();
+        edited.add_ingredient(Ingredient::new("Cigarettes", 5));
+        assert_eq!(
+            Some(Ingredient::new("Base.Milk", 1)),
+            edited.remove_ingredient("Base.Milk")
+        );
+        assert_eq!(None, edited.remove_ingredient("Base.Milk"));
+
+        let expected = Recipe::new(
+            "Make Strong Mildew Cure",
+            vec![
+                Ingredient::new("GardeningSprayEmpty", 1),
+                Ingredient::new("Cigarettes", 5),
+            ],
+            "GardeningSprayCigarettes",
+            60.5,
+            "Farming",
+            true,
+        );
+        assert_eq!(expected, edited);
+
+        let text = edited.to_string();
+        let reparsed: IResult<&str, Recipe, nom::error::Error<&str>> = recipe(&text);
+        let (_, actual) = reparsed.expect("failed to parse displayed recipe");
+
+        assert_eq!(edited, actual);
+    }
+
+    fn arb_ingredient() -> impl Strategy<Value = Ingredient> {
+        prop_oneof![
+            (
+                "([A-Z][A-Za-z0-9]{0,8}\\.)?[A-Za-z][A-Za-z0-9]{0,12}",
+                1..100u32,
+            )
+                .prop_map(|(name, count)| Ingredient::new(name, count)),
+            ("[A-Za-z][A-Za-z0-9]{0,12}", 1..1000u32).prop_map(|(name, tenths)| {
+                Ingredient::with_amount(name, Amount::Float(tenths as f32 / 10.0))
+            }),
+            "[A-Z][A-Za-z0-9]{0,12}".prop_map(Ingredient::tag),
+        ]
+    }
+
+    fn arb_recipe() -> impl Strategy<Value = Recipe> {
+        (
+            "[A-Za-z0-9]{1,8}( [A-Za-z0-9]{1,8}){0,3}",
+            prop::collection::vec(arb_ingredient(), 1..6),
+            "[A-Za-z0-9]{1,16}",
+            0.0..10_000.0f32,
+            "[A-Za-z]{1,10}( [A-Za-z]{1,10}){0,2}",
+            any::<bool>(),
+            prop::collection::vec(("[A-Za-z]{1,10}", 0..10u32), 0..3),
+        )
+            .prop_map(
+                |(name, ingredients, result, time, category, learned, skills)| {
+                    let mut recipe =
+                        Recipe::new(name, ingredients, result, time, category, learned);
+                    recipe.skills_required = skills
+                        .into_iter()
+                        .map(|(skill, level)| SkillReq::new(skill, level))
+                        .collect();
+                    recipe
+                },
+            )
+    }
+
+    proptest! {
+        #[test]
+        fn display_round_trips(expected in arb_recipe()) {
+            let text = expected.to_string();
+            let parsed: IResult<&str, Recipe, nom::error::Error<&str>> = recipe(&text);
+            let (rest, actual) = parsed.expect("failed to parse displayed recipe");
+
+            prop_assert_eq!("", rest);
+            prop_assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn parse_probabilistic_result() {
+        let recipe_text = "recipe Open Mystery Box
+{
+  MysteryBox,
+
+  Result:Foo=2|50%,
+  Time:10.0,
+  Category:General,
+  NeedToBeLearn:false,
+}";
+
+        let recipe_res: Result<Recipe> = recipe(recipe_text);
+        let (_, actual) = recipe_res.expect("failed to parse recipe");
+
+        assert_eq!("Foo", actual.result().item);
+        assert_eq!(2, actual.result().count);
+        assert_eq!(Some(0.5), actual.result().chance);
+    }
+
+    #[test]
+    fn parse_typed_metadata_fields() {
+        let recipe_text = "recipe Make Plank
+{
+  Log,
+
+  Result:Plank=2,
+  Time:230.0,
+  Category:Carpentry,
+  NeedToBeLearn:false,
+  OnCreate:Recipe.OnCreate.Plank,
+  Sound:Hammering,
+  AnimNode:SawLog,
+}";
+
+        let recipe_res: Result<Recipe> = recipe(recipe_text);
+        let (_, actual) = recipe_res.expect("failed to parse recipe");
+
+        assert_eq!(Some("Recipe.OnCreate.Plank"), actual.on_create());
+        assert_eq!(Some("Hammering"), actual.sound());
+        assert_eq!(Some(&AnimNode::SawLog), actual.anim_node());
+        assert_eq!(None, actual.on_test());
+        assert_eq!(None, actual.prop1());
+        assert_eq!(None, actual.prop2());
+        assert!(actual.extra().is_empty());
+    }
+
+    #[test]
+    fn parse_recipe_without_ingredients() {
+        let recipe_text = "recipe Free { Result:Foo, Time:0, Category:Misc, NeedToBeLearn:false, }";
+        let expected = Recipe::new("Free", Vec::<Ingredient>::new(), "Foo", 0.0, "Misc", false);
+
+        let recipe_res: Result<Recipe> = recipe(recipe_text);
+        let (_, actual) = recipe_res.expect("failed to parse recipe");
+
+        assert_eq!(expected, actual);
+
+        let text = actual.to_string();
+        let reparsed: IResult<&str, Recipe, nom::error::Error<&str>> = recipe(&text);
+        let (_, reparsed) = reparsed.expect("failed to parse displayed recipe");
+        assert_eq!(expected, reparsed);
+    }
+
+    #[test]
+    fn leading_and_trailing_counts_are_equivalent() {
+        let leading_text = "recipe Make Plank Wall
+{
+  2 Nails,
+  Plank,
+
+  Result:PlankWall,
+  Time:100.0,
+  Category:Carpentry,
+}";
+        let trailing_text = "recipe Make Plank Wall
+{
+  Nails=2,
+  Plank,
+
+  Result:PlankWall,
+  Time:100.0,
+  Category:Carpentry,
+}";
+
+        let leading_res: Result<Recipe> = recipe(leading_text);
+        let (_, leading) = leading_res.expect("failed to parse recipe");
+        let trailing_res: Result<Recipe> = recipe(trailing_text);
+        let (_, trailing) = trailing_res.expect("failed to parse recipe");
+
+        assert_eq!(
+            &[Ingredient::new("Nails", 2), Ingredient::new("Plank", 1)],
+            leading.ingredients()
+        );
+        assert_eq!(trailing, leading);
+    }
+
+    #[test]
+    fn parse_sub_block_recipe() {
+        let recipe_text = "recipe Make Mildew Cure
+{
+    inputs
+    {
+        GardeningSprayEmpty,
+        Base.Milk,
+    }
+    outputs
+    {
+        GardeningSprayMilk,
+    }
+
+    Time:40.0,
+    Category:Farming,
+    NeedToBeLearn:true,
+}";
+        let expected = Recipe::new(
+            "Make Mildew Cure",
+            vec!["GardeningSprayEmpty", "Base.Milk"],
+            "GardeningSprayMilk",
+            40.0,
+            "Farming",
+            true,
+        );
+
+        let recipe_res: Result<Recipe> = recipe_v2(recipe_text);
+        let (_, actual) = recipe_res.expect("failed to parse recipe");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn semicolon_terminators_require_option() {
+        let recipe_text = "recipe Make Mildew Cure
+{
+    GardeningSprayEmpty,
+
+    Result:GardeningSprayMilk;
+    Time:40.0;
+    Category:Farming;
+    SkillRequired:Farming:2;Cooking:1;
+    OnCreate:Recipe.OnCreate.MakeCure;
+}";
+
+        let strict_res: Result<Recipe> = recipe(recipe_text);
+        assert!(strict_res.is_err());
+
+        let options = ParseOptions {
+            semicolon_terminators: true,
+            ..ParseOptions::default()
+        };
+        let recipe_res: Result<Recipe> = recipe_with_options(options)(recipe_text);
+        let (_, actual) = recipe_res.expect("failed to parse recipe");
+
+        assert_eq!("Farming", actual.category().as_str());
+        assert_eq!(
+            &[SkillReq::new("Farming", 2), SkillReq::new("Cooking", 1)],
+            actual.skills_required()
+        );
+        assert_eq!(Some("Recipe.OnCreate.MakeCure"), actual.on_create());
+    }
+
+    #[test]
+    fn normalized_recipes_display_identically() {
+        let options = ParseOptions {
+            collect_unknown_fields: true,
+            ..ParseOptions::default()
+        };
+        let first_text = "recipe Make Mildew Cure
+{
+    GardeningSprayEmpty,
+    Base.Milk,
+
+    Result:GardeningSprayMilk,
+    Time:40.0,
+    Category:Farming,
+    Tooltip:Cures mildew,
+    IsHidden:false,
+}";
+        let second_text = "recipe Make Mildew Cure
+{
+    Base.Milk,
+    GardeningSprayEmpty,
+
+    IsHidden:false,
+    Category:Farming,
+    Result:GardeningSprayMilk,
+    Tooltip:Cures mildew,
+    Time:40.0,
+}";
+
+        let first_res: Result<Recipe> = recipe_with_options(options)(first_text);
+        let (_, first) = first_res.expect("failed to parse first recipe");
+        let second_res: Result<Recipe> = recipe_with_options(options)(second_text);
+        let (_, second) = second_res.expect("failed to parse second recipe");
+        assert_ne!(first.to_string(), second.to_string());
+
+        assert_eq!(
+            first.normalized(true).to_string(),
+            second.normalized(true).to_string()
+        );
+    }
+
+    #[test]
+    fn parse_tags() {
+        let recipe_text = "recipe Make Mildew Cure
+{
+  GardeningSprayEmpty,
+
+  Result:GardeningSprayMilk,
+  Time:40.0,
+  Category:Farming,
+  Tags:AnySurfaceCraft;Gardening,
+}";
+
+        let recipe_res: Result<Recipe> = recipe(recipe_text);
+        let (_, actual) = recipe_res.expect("failed to parse recipe");
+
+        assert_eq!(
+            &[String::from("AnySurfaceCraft"), String::from("Gardening")],
+            actual.tags()
+        );
+        assert!(actual.has_tag("Gardening"));
+        assert!(!actual.has_tag("Cooking"));
+        assert!(actual
+            .to_string()
+            .contains("    Tags:AnySurfaceCraft;Gardening,\n"));
+    }
+
+    #[test]
+    fn parse_heat_fields() {
+        let recipe_text = "recipe Make Soup
+{
+  Pot,
+
+  Result:PotOfSoup,
+  Time:80.0,
+  Category:Cooking,
+  Heat:1.5,
+  IsHidden:false,
+}";
+        let options = ParseOptions {
+            collect_unknown_fields: true,
+            ..ParseOptions::default()
+        };
+
+        let recipe_res: Result<Recipe> = recipe_with_options(options)(recipe_text);
+        let (_, actual) = recipe_res.expect("failed to parse recipe");
+
+        assert_eq!(Some(1.5), actual.heat());
+        assert_eq!(None, actual.cooking_heat());
+        assert_eq!(
+            &[(String::from("IsHidden"), String::from("false"))],
+            actual.extra()
+        );
+        assert!(actual.to_string().contains("    Heat:1.5,\n"));
+    }
+
+    #[test]
+    fn parse_stop_flags() {
+        let recipe_text = "recipe Saw Logs
+{
+  Log,
+
+  Result:Plank=3,
+  Time:160.0,
+  Category:Carpentry,
+  StopOnWalk:true,
+  StopOnRun:false,
+  StopOnCrawl:true,
+}";
+        let options = ParseOptions {
+            collect_unknown_fields: true,
+            ..ParseOptions::default()
+        };
+
+        let recipe_res: Result<Recipe> = recipe_with_options(options)(recipe_text);
+        let (_, actual) = recipe_res.expect("failed to parse recipe");
+
+        assert!(actual.stop_on_walk());
+        assert!(!actual.stop_on_run());
+        assert_eq!(
+            &[(String::from("StopOnCrawl"), String::from("true"))],
+            actual.extra()
+        );
+    }
+
+    #[test]
+    fn parse_boolean_toggles() {
+        let recipe_text = "recipe Defrost Soup
+{
+  SoupPot,
+
+  Result:SoupPot,
+  Time:30.0,
+  Category:Cooking,
+  AllowFrozenItem:true,
+}";
+
+        let recipe_res: Result<Recipe> = recipe(recipe_text);
+        let (_, actual) = recipe_res.expect("failed to parse recipe");
+
+        assert!(actual.allow_frozen_item());
+        assert!(!actual.remove_result_item());
+        assert!(!actual.in_same_inventory());
+        assert!(actual.to_string().contains("    AllowFrozenItem:true,\n"));
+        assert!(!actual.to_string().contains("RemoveResultItem"));
+    }
+
+    #[test]
+    fn parse_result_with_remove_result_item() {
+        let recipe_text = "recipe Sharpen Knife
+{
+  KitchenKnife,
+  Whetstone,
+
+  RemoveResultItem:true,
+  Result:KitchenKnife,
+  Time:50.0,
+  Category:Survivalist,
+}";
+
+        let recipe_res: Result<Recipe> = recipe(recipe_text);
+        let (_, actual) = recipe_res.expect("failed to parse recipe");
+
+        assert_eq!(&ResultSpec::from("KitchenKnife"), actual.result());
+        assert!(actual.remove_result_item());
+
+        let reordered = actual.to_string();
+        let (_, reparsed) = recipe::<nom::error::Error<&str>>(&reordered)
+            .expect("failed to parse displayed recipe");
+        assert_eq!(actual.result(), reparsed.result());
+        assert!(reparsed.remove_result_item());
+    }
+
+    #[test]
+    fn parse_prop_fields() {
+        let recipe_text = "recipe Make Plank
+{
+  Log,
+
+  Result:Plank=2,
+  Time:230.0,
+  Category:Carpentry,
+  Prop1:Saw,
+  Prop2:Base.Log,
+}";
+
+        let recipe_res: Result<Recipe> = recipe(recipe_text);
+        let (_, actual) = recipe_res.expect("failed to parse recipe");
+
+        assert_eq!(Some("Saw"), actual.prop1());
+        assert_eq!(Some("Base.Log"), actual.prop2());
+        assert!(actual
+            .to_string()
+            .contains("    Prop1:Saw,\n    Prop2:Base.Log,\n"));
+    }
+
+    #[test]
+    fn parse_comma_less_ingredients() {
+        let recipe_text = "recipe Make Mildew Cure
+{
+  GardeningSprayEmpty
+  Base.Milk
+  Nails=2
+
+  Result:GardeningSprayMilk,
+  Time:40.0,
+  Category:Farming,
+  NeedToBeLearn:true,
+}";
+        let expected = Recipe::new(
+            "Make Mildew Cure",
+            vec![
+                Ingredient::new("GardeningSprayEmpty", 1),
+                Ingredient::new("Base.Milk", 1),
+                Ingredient::new("Nails", 2),
+            ],
+            "GardeningSprayMilk",
+            40.0,
+            "Farming",
+            true,
+        );
+
+        let recipe_res: Result<Recipe> = recipe(recipe_text);
+        let (_, actual) = recipe_res.expect("failed to parse recipe");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn parse_recipe_with_brace_on_name_line() {
+        let recipe_text = "recipe Foo {
+  Flour,
+  Result:CakeBatter,
+  Time:50.0,
+  Category:Cooking,
+  NeedToBeLearn:false,
+}";
+        let expected = Recipe::new("Foo", vec!["Flour"], "CakeBatter", 50.0, "Cooking", false);
+
+        let recipe_res: Result<Recipe> = recipe(recipe_text);
+        let (rest, actual) = recipe_res.expect("failed to parse recipe");
+
+        assert_eq!("", rest);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn parse_known_and_unknown_categories() {
+        let farming_text = "recipe Make Mildew Cure
+{
+  GardeningSprayEmpty,
+  Result:GardeningSprayMilk,
+  Time:40.0,
+  Category:Farming,
+  NeedToBeLearn:true,
+}";
+        let modded_text = "recipe Brew Potion
+{
+  Herbs,
+  Result:Potion,
+  Time:40.0,
+  Category:Alchemy,
+  NeedToBeLearn:true,
+}";
+
+        let farming_res: Result<Recipe> = recipe(farming_text);
+        let (_, farming) = farming_res.expect("failed to parse recipe");
+        let modded_res: Result<Recipe> = recipe(modded_text);
+        let (_, modded) = modded_res.expect("failed to parse recipe");
+
+        assert_eq!(&RecipeCategory::Farming, farming.category());
+        assert_eq!(
+            &RecipeCategory::Other(String::from("Alchemy")),
+            modded.category()
+        );
+        assert_eq!("Alchemy", modded.category().as_str());
+    }
+
+    #[test]
+    fn misspelled_field_error_names_the_expected_field() {
+        let recipe_text = "
+recipe Make Mildew Cure
+{
+  GardeningSprayEmpty,
+
+  Reslt:GardeningSprayMilk,
+  Time:40.0,
+  Category:Farming,
+  NeedToBeLearn:true,
+}
+";
+
+        let err = Recipe::try_from(recipe_text).expect_err("parsed a misspelled field");
+        let nom::Err::Error(err) = err else {
+            panic!("expected a recoverable error, got {:?}", err);
+        };
+
+        assert!(err.errors.iter().any(|(input, kind)| {
+            input.trim_start().starts_with("Reslt")
+                && *kind == nom::error::VerboseErrorKind::Context("expected field 'Result'")
+        }));
+    }
+
+    #[test]
+    fn need_to_be_learn_defaults_to_false() {
+        let recipe_text = "recipe Make Cake Batter
+{
+  Flour,
+
+  Result:CakeBatter,
+  Time:50.0,
+  Category:Cooking,
+}";
+        let expected = Recipe::new(
+            "Make Cake Batter",
+            vec!["Flour"],
+            "CakeBatter",
+            50.0,
+            "Cooking",
+            false,
+        );
+
+        let recipe_res: Result<Recipe> = recipe(recipe_text);
+        let (_, actual) = recipe_res.expect("failed to parse recipe");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn empty_input_is_reported() {
+        for recipe_text in ["", "  \n\t"] {
+            let err = Recipe::try_from(recipe_text).expect_err("parsed an empty recipe");
+            let nom::Err::Error(err) = err else {
+                panic!("expected a recoverable error, got {:?}", err);
+            };
+
+            assert!(err
+                .errors
+                .iter()
+                .any(|(_, kind)| *kind == nom::error::VerboseErrorKind::Context("empty input")));
+        }
+    }
+
+    #[test]
+    fn parse_truncated_recipe() {
+        let recipe_text = "recipe Make Cake Batter
+{
+  Flour,
+
+  Result:CakeBatter,
+  Time:50.0,
+  Category:Cooking";
+        let expected = Recipe::new(
+            "Make Cake Batter",
+            vec!["Flour"],
+            "CakeBatter",
+            50.0,
+            "Cooking",
+            false,
+        );
+
+        let recipe_res: Result<(Recipe, bool)> = recipe_partial(recipe_text);
+        let (rest, actual) = recipe_res.expect("failed to parse recipe");
+
+        assert_eq!("", rest);
+        assert_eq!((expected, false), actual);
+    }
+
+    #[test]
+    fn partial_parse_of_complete_recipe() {
+        let recipe_text = "recipe Make Cake Batter
+{
+  Flour,
+
+  Result:CakeBatter,
+  Time:50.0,
+  Category:Cooking,
+}";
+
+        let recipe_res: Result<(Recipe, bool)> = recipe_partial(recipe_text);
+        let (_, (_, complete)) = recipe_res.expect("failed to parse recipe");
+
+        assert!(complete);
+    }
 }
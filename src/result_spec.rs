@@ -0,0 +1,159 @@
+use std::fmt;
+
+use nom::{
+    bytes::complete::tag,
+    character::complete::u32,
+    combinator::{map, opt},
+    error::ParseError,
+    number::complete::double,
+    sequence::{delimited, preceded, tuple},
+    IResult,
+};
+
+use crate::identifier1;
+
+/// What a recipe produces: an item, how many of it, and for probabilistic
+/// recipes the chance of producing it at all.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct ResultSpec {
+    pub item: String,
+    pub count: u32,
+    pub chance: Option<f32>,
+}
+
+impl ResultSpec {
+    pub fn new(item: impl Into<String>, count: u32) -> Self {
+        Self {
+            item: item.into(),
+            count,
+            chance: None,
+        }
+    }
+
+    pub fn with_chance(mut self, chance: f32) -> Self {
+        self.chance = Some(chance);
+        self
+    }
+}
+
+impl From<&str> for ResultSpec {
+    fn from(item: &str) -> Self {
+        ResultSpec::new(item, 1)
+    }
+}
+
+impl From<String> for ResultSpec {
+    fn from(item: String) -> Self {
+        ResultSpec::new(item, 1)
+    }
+}
+
+impl fmt::Display for ResultSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.item)?;
+        if self.count != 1 || self.chance.is_some() {
+            write!(f, "={}", self.count)?;
+        }
+        if let Some(chance) = self.chance {
+            write!(f, "|{}%", format_percent(chance))?;
+        }
+        Ok(())
+    }
+}
+
+/// `chance` as a percentage, with the fewest decimal places that parse back
+/// to the same chance. Plain formatting of `chance * 100.0` would show the
+/// float noise from the division in `result_spec`, as in `33.000004`.
+fn format_percent(chance: f32) -> String {
+    let percent = f64::from(chance) * 100.0;
+    let round_trips = |text: &str| {
+        text.parse::<f64>()
+            .is_ok_and(|p| chance_from_percent(p) == chance)
+    };
+    (0..=12)
+        .map(|places| format!("{:.*}", places, percent))
+        .find(|text| round_trips(text))
+        .unwrap_or_else(|| percent.to_string())
+}
+
+/// Dividing in `f64` keeps the percentage's precision until the final
+/// rounding, so that every `f32` chance has a percentage that reads back
+/// as exactly that chance.
+fn chance_from_percent(percent: f64) -> f32 {
+    (percent / 100.0) as f32
+}
+
+/// A `Result` value such as `Foo`, `Base.Foo=2` or `Foo=2|50%`.
+pub fn result_spec<'a, E>(input: &'a str) -> IResult<&'a str, ResultSpec, E>
+where
+    E: ParseError<&'a str>,
+{
+    map(
+        tuple((
+            identifier1,
+            opt(preceded(tag("="), u32)),
+            opt(delimited(tag("|"), double, tag("%"))),
+        )),
+        |(item, count, percent): (&str, _, _)| ResultSpec {
+            item: item.to_string(),
+            count: count.unwrap_or(1),
+            chance: percent.map(chance_from_percent),
+        },
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Result<T> = IResult<&'static str, T, nom::error::Error<&'static str>>;
+
+    #[test]
+    fn parse_result_with_chance() {
+        let result_res: Result<ResultSpec> = result_spec("Foo=2|50%,");
+        let (rest, actual) = result_res.expect("failed to parse result");
+
+        assert_eq!(",", rest);
+        assert_eq!(ResultSpec::new("Foo", 2).with_chance(0.5), actual);
+    }
+
+    #[test]
+    fn parse_bare_result() {
+        let result_res: Result<ResultSpec> = result_spec("Foo,");
+        let (_, actual) = result_res.expect("failed to parse result");
+
+        assert_eq!(ResultSpec::new("Foo", 1), actual);
+    }
+
+    #[test]
+    fn result_display_round_trips() {
+        let result_text = "Base.Foo=2|33%";
+        let result_res: Result<ResultSpec> = result_spec(result_text);
+        let (_, actual) = result_res.expect("failed to parse result");
+
+        assert_eq!(ResultSpec::new("Base.Foo", 2).with_chance(0.33), actual);
+        assert_eq!(result_text, actual.to_string());
+
+        let displayed = actual.to_string();
+        let reparsed: IResult<&str, ResultSpec, nom::error::Error<&str>> = result_spec(&displayed);
+        assert_eq!(Ok(("", actual)), reparsed);
+    }
+
+    #[test]
+    fn fractional_chance_round_trips() {
+        for chance in [0.123456, 0.001, 0.333_333_34, 1.0 / 7.0] {
+            let expected = ResultSpec::new("Foo", 2).with_chance(chance);
+            let displayed = expected.to_string();
+            let reparsed: IResult<&str, ResultSpec, nom::error::Error<&str>> =
+                result_spec(&displayed);
+
+            assert_eq!(Ok(("", expected)), reparsed, "displayed as {:?}", displayed);
+        }
+        assert_eq!(
+            "Foo=2|12.3456%",
+            ResultSpec::new("Foo", 2).with_chance(0.123456).to_string()
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use std::fmt;
+
+use nom::{
+    bytes::complete::tag,
+    character::complete::{alphanumeric1, u32},
+    combinator::map,
+    error::ParseError,
+    sequence::separated_pair,
+    IResult,
+};
+
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SkillReq {
+    pub skill: String,
+    pub level: u32,
+}
+
+impl SkillReq {
+    pub fn new(skill: impl Into<String>, level: u32) -> Self {
+        Self {
+            skill: skill.into(),
+            level,
+        }
+    }
+}
+
+impl fmt::Display for SkillReq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.skill, self.level)
+    }
+}
+
+pub fn skill_requirement<'a, E>(input: &'a str) -> IResult<&'a str, SkillReq, E>
+where
+    E: ParseError<&'a str>,
+{
+    map(
+        separated_pair(alphanumeric1, tag(":"), u32),
+        |(skill, level)| SkillReq::new(skill, level),
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Result<T> = IResult<&'static str, T, nom::error::Error<&'static str>>;
+
+    #[test]
+    fn parse_skill_requirement() {
+        let skill_res: Result<SkillReq> = skill_requirement("Cooking:3,");
+        let (rest, actual) = skill_res.expect("failed to parse skill requirement");
+
+        assert_eq!(",", rest);
+        assert_eq!(SkillReq::new("Cooking", 3), actual);
+    }
+}
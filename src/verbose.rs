@@ -0,0 +1,51 @@
+//! The parsers specialized to [`VerboseError`], for debugging scripts that
+//! don't parse. The errors record the context of every parser that failed
+//! on the way down, which [`describe`] turns into a readable trace.
+
+use nom::{
+    error::{convert_error, VerboseError},
+    Err, IResult,
+};
+
+use crate::{Definition, ModuleBlock, Recipe};
+
+pub type Error<'a> = VerboseError<&'a str>;
+
+pub fn recipe(input: &str) -> IResult<&str, Recipe, Error<'_>> {
+    crate::recipe(input)
+}
+
+pub fn parse_file(input: &str) -> Result<Vec<ModuleBlock<Definition>>, Err<Error<'_>>> {
+    crate::parse_file(input)
+}
+
+/// Render `err` as a trace of lines from `input`, one per context, with the
+/// failing position marked. Incomplete input has no position to show.
+pub fn describe(input: &str, err: Err<Error<'_>>) -> String {
+    match err {
+        Err::Error(err) | Err::Failure(err) => convert_error(input, err),
+        Err::Incomplete(needed) => format!("incomplete input: {:?}", needed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describe_missing_field() {
+        let recipe_text = "recipe Make Mildew Cure
+{
+    GardeningSprayEmpty,
+
+    Result:GardeningSprayMilk,
+    Category:Farming,
+}";
+
+        let err = recipe(recipe_text).expect_err("parsed a recipe without a time");
+        let actual = describe(recipe_text, err);
+
+        assert!(actual.lines().count() > 1);
+        assert!(actual.contains("expected field 'Time'"));
+    }
+}
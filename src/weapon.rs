@@ -0,0 +1,59 @@
+use nom::{
+    character::complete::u32, combinator::all_consuming, error::ParseError, number::complete::float,
+};
+
+/// The numeric combat fields of an item with `Type = Weapon`.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct WeaponStats {
+    pub min_damage: Option<f32>,
+    pub max_damage: Option<f32>,
+    pub min_range: Option<f32>,
+    pub max_range: Option<f32>,
+    pub min_angle: Option<f32>,
+    pub swing_time: Option<f32>,
+    pub critical_chance: Option<f32>,
+    pub crit_dmg_multiplier: Option<f32>,
+    pub max_hit_count: Option<u32>,
+}
+
+impl WeaponStats {
+    /// Move the weapon fields out of `fields`, parsing their values. All
+    /// other fields are left where they are.
+    pub(crate) fn extract<'a, E>(
+        fields: &mut Vec<(&'a str, &'a str)>,
+    ) -> Result<WeaponStats, nom::Err<E>>
+    where
+        E: ParseError<&'a str>,
+    {
+        let mut stats = WeaponStats::default();
+        let mut remaining = Vec::with_capacity(fields.len());
+        for (name, value) in fields.drain(..) {
+            if !stats.set(name, value)? {
+                remaining.push((name, value));
+            }
+        }
+        *fields = remaining;
+        Ok(stats)
+    }
+
+    fn set<'a, E>(&mut self, name: &str, value: &'a str) -> Result<bool, nom::Err<E>>
+    where
+        E: ParseError<&'a str>,
+    {
+        let number = |value| all_consuming(float)(value).map(|(_, n)| Some(n));
+        match name {
+            "MinDamage" => self.min_damage = number(value)?,
+            "MaxDamage" => self.max_damage = number(value)?,
+            "MinRange" => self.min_range = number(value)?,
+            "MaxRange" => self.max_range = number(value)?,
+            "MinAngle" => self.min_angle = number(value)?,
+            "SwingTime" => self.swing_time = number(value)?,
+            "CriticalChance" => self.critical_chance = number(value)?,
+            "CritDmgMultiplier" => self.crit_dmg_multiplier = number(value)?,
+            "MaxHitCount" => self.max_hit_count = Some(all_consuming(u32)(value)?.1),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}